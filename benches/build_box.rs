@@ -1,16 +1,30 @@
-#![feature(test)]
-
 extern crate rand;
-extern crate test;
 extern crate wfc;
 
+use std::time::Instant;
+
 use rand::{Isaac64Rng, Rng};
 
 use wfc::field::Field;
 use wfc::entry::CharacterEntry;
 
-#[bench]
-fn closed_box(b: &mut test::Bencher) {
+// The test crate is nightly only, so time the iterations by hand.
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        closed_box();
+    }
+
+    println!(
+        "closed_box: {:?} per iteration",
+        start.elapsed() / ITERATIONS
+    );
+}
+
+fn closed_box() {
     let potentials = [
         CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
         CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
@@ -24,30 +38,27 @@ fn closed_box(b: &mut test::Bencher) {
         // CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
     ];
 
-    b.iter(|| {
-        let mut rng = Isaac64Rng::new_unseeded();
+    let mut rng = Isaac64Rng::new_unseeded();
 
-        let mut field = Field::new(&potentials, 60, 30);
-        if field.close_edges() {
-            for _ in 0..100 {
-                if run_field(field.clone(), &mut rng) {
-                    break;
-                }
+    let mut field = Field::new(&potentials, 60, 30);
+    if field.close_edges() {
+        for _ in 0..100 {
+            if run_field(field.clone(), &mut rng) {
+                break;
             }
         }
-    });
+    }
 }
 
 
 fn run_field<R: Rng> (mut field: Field, mut rng: &mut R) -> bool {
     loop {
-        if let Some(_) = field.render() {
+        if field.render().is_some() {
             return true;
         }
 
-        if field.step(&mut rng) == false {
+        if !field.step(&mut rng) {
             return false;
         }
     }
 }
-
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use field::{Field, MASKED};
+use observer::FieldObserver;
+
+pub type Color = [u8; 3];
+
+// How a single cell is drawn: a row major bitmap of width * height pixels.
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pixels: Vec<Color>,
+}
+
+impl Tile {
+    pub fn solid(color: Color, width: usize, height: usize) -> Tile {
+        Tile {
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn from_bitmap(pixels: Vec<Color>) -> Tile {
+        Tile { pixels }
+    }
+}
+
+// Records the state of a Field as it is being solved, and writes the result
+// as an animated GIF.
+//
+// Call record after every step.  Only every stride-th step is kept, except
+// for steps that backtracked, which are always kept.  To catch those, pass
+// the recorder as the observer to Field::step_observed.
+pub struct GifRecorder {
+    tiles: Vec<Tile>,
+    unknown: Tile,
+    contradiction: Tile,
+    masked: Tile,
+    tile_width: usize,
+    tile_height: usize,
+
+    stride: usize,
+    delay: u16,
+
+    num_steps: usize,
+    backtracked: bool,
+    frames: Vec<Vec<Vec<usize>>>,
+}
+
+impl GifRecorder {
+    // One tile per potential, all of them tile_width by tile_height pixels.
+    pub fn new(tiles: Vec<Tile>, tile_width: usize, tile_height: usize) -> GifRecorder {
+        GifRecorder {
+            tiles,
+            unknown: Tile::solid([128, 128, 128], tile_width, tile_height),
+            contradiction: Tile::solid([255, 0, 0], tile_width, tile_height),
+            masked: Tile::solid([0, 0, 0], tile_width, tile_height),
+            tile_width,
+            tile_height,
+            stride: 1,
+            delay: 5,
+            num_steps: 0,
+            backtracked: false,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn with_stride(self, stride: usize) -> GifRecorder {
+        GifRecorder {
+            stride: stride.max(1),
+            ..self
+        }
+    }
+
+    // The time between frames, in hundredths of a second.
+    pub fn with_delay(self, delay: u16) -> GifRecorder {
+        GifRecorder { delay, ..self }
+    }
+
+    pub fn with_unknown(self, unknown: Tile) -> GifRecorder {
+        GifRecorder { unknown, ..self }
+    }
+
+    pub fn with_contradiction(self, contradiction: Tile) -> GifRecorder {
+        GifRecorder {
+            contradiction,
+            ..self
+        }
+    }
+
+    pub fn with_masked(self, masked: Tile) -> GifRecorder {
+        GifRecorder { masked, ..self }
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Fails if the recorder does not have one tile per potential of the field.
+    pub fn record(&mut self, field: &Field) -> Result<(), String> {
+        self.check_tiles(field)?;
+
+        self.num_steps += 1;
+
+        if self.backtracked || self.num_steps.is_multiple_of(self.stride) {
+            self.frames.push(field.render_partial());
+        }
+
+        self.backtracked = false;
+
+        Ok(())
+    }
+
+    // Records the field regardless of the stride, e.g. for the final result.
+    pub fn record_frame(&mut self, field: &Field) -> Result<(), String> {
+        self.check_tiles(field)?;
+
+        self.frames.push(field.render_partial());
+
+        Ok(())
+    }
+
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let (palette, color_indices) = self.build_palette()?;
+
+        let (width, height) = match self.frames.first() {
+            Some(frame) => (
+                frame.first().map_or(0, |row| row.len()) * self.tile_width,
+                frame.len() * self.tile_height,
+            ),
+            None => (0, 0),
+        };
+
+        if width > 0xffff || height > 0xffff {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The field is too large to fit in a GIF.",
+            ));
+        }
+
+        // The color table must be a power of 2, with at least 2 entries.
+        let mut table_bits = 1;
+        while (1 << table_bits) < palette.len() {
+            table_bits += 1;
+        }
+
+        out.write_all(b"GIF89a")?;
+        write_u16(out, width as u16)?;
+        write_u16(out, height as u16)?;
+        out.write_all(&[0xf0 | (table_bits - 1) as u8, 0, 0])?;
+
+        for i in 0..(1 << table_bits) {
+            out.write_all(&palette.get(i).cloned().unwrap_or([0, 0, 0]))?;
+        }
+
+        // Loop forever.
+        out.write_all(&[0x21, 0xff, 0x0b])?;
+        out.write_all(b"NETSCAPE2.0")?;
+        out.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        for frame in &self.frames {
+            out.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+            write_u16(out, self.delay)?;
+            out.write_all(&[0x00, 0x00])?;
+
+            out.write_all(&[0x2c, 0, 0, 0, 0])?;
+            write_u16(out, width as u16)?;
+            write_u16(out, height as u16)?;
+            out.write_all(&[0x00])?;
+
+            let pixels = self.rasterize(frame, &color_indices, width, height);
+
+            let min_code_size = table_bits.max(2);
+            out.write_all(&[min_code_size as u8])?;
+            write_sub_blocks(out, &lzw_encode(&pixels, min_code_size))?;
+        }
+
+        out.write_all(&[0x3b])
+    }
+
+    fn check_tiles(&self, field: &Field) -> Result<(), String> {
+        if self.tiles.len() != field.num_potentials() {
+            return Err(format!(
+                "The recorder has {} tiles, but the field has {} potentials.",
+                self.tiles.len(),
+                field.num_potentials()
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn tile(&self, index: usize) -> &Tile {
+        if index < self.tiles.len() {
+            &self.tiles[index]
+        } else if index == self.tiles.len() {
+            &self.unknown
+        } else if index == MASKED {
+            &self.masked
+        } else {
+            &self.contradiction
+        }
+    }
+
+    fn all_tiles(&self) -> Vec<&Tile> {
+        let mut tiles: Vec<&Tile> = self.tiles.iter().collect();
+        tiles.push(&self.unknown);
+        tiles.push(&self.contradiction);
+        tiles.push(&self.masked);
+        tiles
+    }
+
+    fn build_palette(&self) -> io::Result<(Vec<Color>, HashMap<Color, u8>)> {
+        let mut palette = Vec::new();
+        let mut color_indices = HashMap::new();
+
+        for tile in self.all_tiles() {
+            if tile.pixels.len() != self.tile_width * self.tile_height {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Every tile must have tile_width * tile_height pixels.",
+                ));
+            }
+
+            for color in &tile.pixels {
+                if !color_indices.contains_key(color) {
+                    if palette.len() == 256 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "The tiles use more than 256 colors.",
+                        ));
+                    }
+
+                    color_indices.insert(*color, palette.len() as u8);
+                    palette.push(*color);
+                }
+            }
+        }
+
+        Ok((palette, color_indices))
+    }
+
+    fn rasterize(
+        &self,
+        frame: &[Vec<usize>],
+        color_indices: &HashMap<Color, u8>,
+        width: usize,
+        height: usize,
+    ) -> Vec<u8> {
+        let mut pixels = vec![0; width * height];
+
+        for (cell_y, row) in frame.iter().enumerate() {
+            for (cell_x, index) in row.iter().enumerate() {
+                let tile = self.tile(*index);
+
+                for tile_y in 0..self.tile_height {
+                    let y = cell_y * self.tile_height + tile_y;
+
+                    for tile_x in 0..self.tile_width {
+                        let x = cell_x * self.tile_width + tile_x;
+                        let color = &tile.pixels[tile_y * self.tile_width + tile_x];
+
+                        pixels[y * width + x] = color_indices[color];
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+}
+
+impl FieldObserver for GifRecorder {
+    fn on_backtrack(&mut self, _x: usize, _y: usize, _potential_index: usize) {
+        self.backtracked = true;
+    }
+}
+
+fn write_u16<W: Write>(out: &mut W, value: u16) -> io::Result<()> {
+    out.write_all(&[(value & 0xff) as u8, (value >> 8) as u8])
+}
+
+fn write_sub_blocks<W: Write>(out: &mut W, data: &[u8]) -> io::Result<()> {
+    for block in data.chunks(255) {
+        out.write_all(&[block.len() as u8])?;
+        out.write_all(block)?;
+    }
+
+    out.write_all(&[0x00])
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    num_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u32, size: u32) {
+        self.current |= code << self.num_bits;
+        self.num_bits += size;
+
+        while self.num_bits >= 8 {
+            self.bytes.push((self.current & 0xff) as u8);
+            self.current >>= 8;
+            self.num_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.num_bits > 0 {
+            self.bytes.push((self.current & 0xff) as u8);
+        }
+
+        self.bytes
+    }
+}
+
+const MAX_CODE: u32 = 4096;
+
+fn lzw_encode(pixels: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear_code = 1 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        current: 0,
+        num_bits: 0,
+    };
+
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+    let mut table: HashMap<(u32, u8), u32> = HashMap::new();
+
+    writer.write(clear_code, code_size);
+
+    let mut pixel_iter = pixels.iter();
+
+    if let Some(first) = pixel_iter.next() {
+        let mut prefix = u32::from(*first);
+
+        for pixel in pixel_iter {
+            if let Some(code) = table.get(&(prefix, *pixel)) {
+                prefix = *code;
+                continue;
+            }
+
+            writer.write(prefix, code_size);
+
+            if next_code < MAX_CODE {
+                table.insert((prefix, *pixel), next_code);
+                next_code += 1;
+
+                // The decoder is one entry behind, so only grow once the code
+                // just added no longer fits.
+                if next_code > (1 << code_size) && code_size < 12 {
+                    code_size += 1;
+                }
+            } else {
+                writer.write(clear_code, code_size);
+                table.clear();
+                next_code = end_code + 1;
+                code_size = min_code_size + 1;
+            }
+
+            prefix = u32::from(*pixel);
+        }
+
+        writer.write(prefix, code_size);
+    }
+
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // A straightforward decoder to check the encoder against.
+    fn lzw_decode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear_code = 1 << min_code_size;
+        let end_code = clear_code + 1;
+
+        let mut result = Vec::new();
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+
+        let mut current = 0u32;
+        let mut num_bits = 0;
+        let mut bytes = data.iter();
+
+        loop {
+            while num_bits < code_size {
+                current |= u32::from(*bytes.next().unwrap()) << num_bits;
+                num_bits += 8;
+            }
+
+            let code = current & ((1 << code_size) - 1);
+            current >>= code_size;
+            num_bits -= code_size;
+
+            if code == clear_code {
+                table = (0..clear_code).map(|c| vec![c as u8]).collect();
+                table.push(Vec::new());
+                table.push(Vec::new());
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+
+            if code == end_code {
+                return result;
+            }
+
+            let entry = if (code as usize) < table.len() {
+                table[code as usize].clone()
+            } else {
+                let mut entry = previous.clone().unwrap();
+                entry.push(entry[0]);
+                entry
+            };
+
+            result.extend(&entry);
+
+            if let Some(mut new_entry) = previous {
+                if table.len() < MAX_CODE as usize {
+                    new_entry.push(entry[0]);
+                    table.push(new_entry);
+
+                    if table.len() == (1 << code_size) && code_size < 12 {
+                        code_size += 1;
+                    }
+                }
+            }
+
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let mut pixels = Vec::new();
+        for i in 0..20000u32 {
+            pixels.push(((i * 7 + i / 13) % 5) as u8);
+        }
+
+        let encoded = lzw_encode(&pixels, 3);
+        assert_eq!(lzw_decode(&encoded, 3), pixels);
+
+        let encoded = lzw_encode(&[1, 1, 1, 1, 1, 1], 2);
+        assert_eq!(lzw_decode(&encoded, 2), vec![1, 1, 1, 1, 1, 1]);
+    }
+}
@@ -25,9 +25,45 @@ impl Direction {
         Direction::West,
     ];
 
+    // The (x, y) step to the neighbor in this direction, with y growing south.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::NorthWest => (-1, -1),
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL_DIRECTIONS[self.to_opposite_index()]
+    }
 
-    fn to_index(&self) -> usize {
-        match *self {
+    // The direction after swapping east and west.
+    pub fn mirror_horizontal(self) -> Direction {
+        let (dx, dy) = self.offset();
+        Direction::from_offset(-dx, dy)
+    }
+
+    // The direction after swapping north and south.
+    pub fn mirror_vertical(self) -> Direction {
+        let (dx, dy) = self.offset();
+        Direction::from_offset(dx, -dy)
+    }
+
+    fn from_offset(dx: isize, dy: isize) -> Direction {
+        *Direction::ALL_DIRECTIONS
+            .iter()
+            .find(|direction| direction.offset() == (dx, dy))
+            .unwrap()
+    }
+
+    fn to_index(self) -> usize {
+        match self {
             Direction::NorthWest => 0,
             Direction::North => 1,
             Direction::NorthEast => 2,
@@ -39,8 +75,8 @@ impl Direction {
         }
     }
 
-    fn to_opposite_index(&self) -> usize {
-        match *self {
+    fn to_opposite_index(self) -> usize {
+        match self {
             Direction::NorthWest => 4,
             Direction::North => 5,
             Direction::NorthEast => 6,
@@ -53,13 +89,14 @@ impl Direction {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Boundary {
     required: [bool; 8],
     opposite_required: [bool; 8],
 }
 
 impl Boundary {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(borders: &str) -> Result<Boundary, String> {
         if borders.len() != 11 {
             Err(format!(
@@ -86,10 +123,10 @@ impl Boundary {
 
             for (c, possible_direction) in borders.as_bytes().iter().zip(&directions) {
                 if let Some(direction) = *possible_direction {
-                    if *c == ('1' as u8) {
+                    if *c == b'1' {
                         result.required[direction.to_index()] = true;
                         result.opposite_required[direction.to_opposite_index()] = true;
-                    } else if *c != ('0' as u8) {
+                    } else if *c != b'0' {
                         return Err(format!(
                             "Found invalid character '{}' in Bound::from_str(\"{}\")",
                             (*c as char),
@@ -112,6 +149,35 @@ impl Boundary {
     pub fn requires(&self, direction: Direction) -> bool {
         self.required[direction.to_index()]
     }
+
+    // The boundary of the tile flipped left to right.
+    pub fn mirror_horizontal(&self) -> Boundary {
+        self.remap(Direction::mirror_horizontal)
+    }
+
+    // The boundary of the tile flipped top to bottom.
+    pub fn mirror_vertical(&self) -> Boundary {
+        self.remap(Direction::mirror_vertical)
+    }
+
+    // The boundary of the tile turned half way around.
+    pub fn rotate_half(&self) -> Boundary {
+        self.remap(Direction::opposite)
+    }
+
+    fn remap<F: Fn(Direction) -> Direction>(&self, map: F) -> Boundary {
+        let mut result = Boundary::default();
+
+        for direction in &Direction::ALL_DIRECTIONS {
+            if self.requires(*direction) {
+                let mapped = map(*direction);
+                result.required[mapped.to_index()] = true;
+                result.opposite_required[mapped.to_opposite_index()] = true;
+            }
+        }
+
+        result
+    }
 }
 
 #[cfg(test)]
@@ -153,4 +219,23 @@ mod tests {
 
         assert!(se_e_w.fits(&nw_e, Direction::SouthEast));
     }
+
+    #[test]
+    fn boundaries_mirror() {
+        let corner = Boundary::from_str("100|001|010").unwrap();
+
+        assert_eq!(
+            corner.mirror_horizontal(),
+            Boundary::from_str("001|100|010").unwrap()
+        );
+        assert_eq!(
+            corner.mirror_vertical(),
+            Boundary::from_str("010|001|100").unwrap()
+        );
+        assert_eq!(
+            corner.rotate_half(),
+            Boundary::from_str("010|100|001").unwrap()
+        );
+        assert_eq!(corner.rotate_half().rotate_half(), corner);
+    }
 }
@@ -0,0 +1,141 @@
+use std::fmt::Debug;
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, Range};
+
+use boundary::Direction;
+use field::Field;
+use footprint::parts_fit;
+
+// Decides which potential Field::step collapses the selected cell to.
+pub trait ValueChooser: Debug + Send + Sync {
+    // Returns one of the cell's allowed potentials, or None to give up on
+    // the step.
+    fn choose(&self, field: &Field, x: usize, y: usize, rng: &mut dyn Rng) -> Option<usize>;
+}
+
+// Samples proportionally to each potential's weight, including neighbor
+// preferences.  This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedRandom;
+
+impl ValueChooser for WeightedRandom {
+    fn choose(&self, field: &Field, x: usize, y: usize, mut rng: &mut dyn Rng) -> Option<usize> {
+        let normalized_range = Range::new(0.0, 1.0f32);
+        let mut total_weight = 0.0;
+        let mut current_choice = None;
+
+        for (index, current_weight) in field.weights_at(x, y) {
+            total_weight += current_weight;
+
+            if normalized_range.ind_sample(&mut rng) * total_weight < current_weight {
+                current_choice = Some(index);
+            }
+        }
+
+        current_choice
+    }
+}
+
+// Always the heaviest potential, preferring the lowest index on ties.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxWeight;
+
+impl ValueChooser for MaxWeight {
+    fn choose(&self, field: &Field, x: usize, y: usize, _rng: &mut dyn Rng) -> Option<usize> {
+        let mut result: Option<(usize, f32)> = None;
+
+        for (index, weight) in field.weights_at(x, y) {
+            if result.is_none_or(|(_, best_weight)| weight > best_weight) {
+                result = Some((index, weight));
+            }
+        }
+
+        result.map(|(index, _)| index)
+    }
+}
+
+// The potential that removes the fewest allowed potentials from the cell's
+// neighbors, preferring the heavier potential on ties.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastConstraining;
+
+impl ValueChooser for LeastConstraining {
+    fn choose(&self, field: &Field, x: usize, y: usize, _rng: &mut dyn Rng) -> Option<usize> {
+        let mut result: Option<(usize, usize, f32)> = None;
+
+        for (index, weight) in field.weights_at(x, y) {
+            let num_removed = count_removed(field, x, y, index);
+
+            let better = match result {
+                None => true,
+                Some((_, best_removed, best_weight)) => {
+                    num_removed < best_removed
+                        || (num_removed == best_removed && weight > best_weight)
+                }
+            };
+
+            if better {
+                result = Some((index, num_removed, weight));
+            }
+        }
+
+        result.map(|(index, _, _)| index)
+    }
+}
+
+fn count_removed(field: &Field, x: usize, y: usize, potential_index: usize) -> usize {
+    let part = field.part(potential_index);
+    let boundary = field.boundary(potential_index);
+    let mut num_removed = 0;
+
+    for direction in &Direction::ALL_DIRECTIONS {
+        if let Some((test_x, test_y)) = field.adjacent(x, y, *direction) {
+            num_removed += field
+                .allowed_potentials(test_x, test_y)
+                .into_iter()
+                .filter(|test_index| {
+                    !parts_fit(
+                        part,
+                        boundary,
+                        field.part(*test_index),
+                        field.boundary(*test_index),
+                        *direction,
+                    )
+                })
+                .count();
+        }
+    }
+
+    num_removed
+}
+
+// Hands the choice to a callback, which receives the cell's coordinates and
+// its allowed potentials.
+pub struct ChooserFn<F> {
+    callback: F,
+}
+
+impl<F> ChooserFn<F>
+where
+    F: Fn(usize, usize, &[usize]) -> Option<usize> + Send + Sync,
+{
+    pub fn new(callback: F) -> ChooserFn<F> {
+        ChooserFn { callback }
+    }
+}
+
+impl<F> Debug for ChooserFn<F> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "ChooserFn")
+    }
+}
+
+impl<F> ValueChooser for ChooserFn<F>
+where
+    F: Fn(usize, usize, &[usize]) -> Option<usize> + Send + Sync,
+{
+    fn choose(&self, field: &Field, x: usize, y: usize, _rng: &mut dyn Rng) -> Option<usize> {
+        (self.callback)(x, y, &field.allowed_potentials(x, y))
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use boundary::Direction;
+use field::{Field, MASKED};
+
+// Generates an unbounded world out of fixed size chunks, each one solved with
+// a copy of a prototype Field.  Chunks are generated on demand, and their
+// border cells are constrained to fit any neighboring chunks that already
+// exist.
+//
+// Each chunk's random number generator is seeded from the world seed and the
+// chunk coordinates.  A chunk's contents still depend on the order chunks are
+// generated in: its borders are constrained by whichever neighbors exist at
+// the time, so the same chunk can come out differently when its neighbors are
+// generated before it rather than after.  Generate chunks in the same order
+// to reproduce a world.
+pub struct ChunkManager {
+    prototype: Field,
+    seed: u64,
+    max_attempts: usize,
+    chunks: HashMap<(i64, i64), Vec<Vec<usize>>>,
+}
+
+impl ChunkManager {
+    pub fn new(prototype: Field, seed: u64) -> ChunkManager {
+        ChunkManager {
+            prototype,
+            seed,
+            max_attempts: 20,
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_attempts(self, max_attempts: usize) -> ChunkManager {
+        ChunkManager {
+            max_attempts,
+            ..self
+        }
+    }
+
+    pub fn chunk_width(&self) -> usize {
+        self.prototype.width()
+    }
+
+    pub fn chunk_height(&self) -> usize {
+        self.prototype.height()
+    }
+
+    // Returns the chunk if it has already been generated.
+    pub fn get(&self, chunk_x: i64, chunk_y: i64) -> Option<&Vec<Vec<usize>>> {
+        self.chunks.get(&(chunk_x, chunk_y))
+    }
+
+    // Returns the chunk, generating it first if needed.
+    pub fn chunk(&mut self, chunk_x: i64, chunk_y: i64) -> Result<&Vec<Vec<usize>>, String> {
+        if !self.chunks.contains_key(&(chunk_x, chunk_y)) {
+            let indices = self.generate(chunk_x, chunk_y)?;
+            self.chunks.insert((chunk_x, chunk_y), indices);
+        }
+
+        Ok(&self.chunks[&(chunk_x, chunk_y)])
+    }
+
+    // Returns the potential at world coordinate (x, y), generating its chunk
+    // first if needed.
+    pub fn cell(&mut self, x: i64, y: i64) -> Result<usize, String> {
+        let (chunk_x, chunk_y, local_x, local_y) = self.locate(x, y);
+        let chunk = self.chunk(chunk_x, chunk_y)?;
+
+        Ok(chunk[local_y][local_x])
+    }
+
+    fn locate(&self, x: i64, y: i64) -> (i64, i64, usize, usize) {
+        let width = self.chunk_width() as i64;
+        let height = self.chunk_height() as i64;
+
+        (
+            x.div_euclid(width),
+            y.div_euclid(height),
+            x.rem_euclid(width) as usize,
+            y.rem_euclid(height) as usize,
+        )
+    }
+
+    // Looks up an already generated world cell without generating anything.
+    fn generated_cell(&self, x: i64, y: i64) -> Option<usize> {
+        let (chunk_x, chunk_y, local_x, local_y) = self.locate(x, y);
+
+        self.get(chunk_x, chunk_y)
+            .map(|chunk| chunk[local_y][local_x])
+    }
+
+    fn generate(&self, chunk_x: i64, chunk_y: i64) -> Result<Vec<Vec<usize>>, String> {
+        let mut field = self.prototype.clone();
+
+        if !self.constrain_borders(&mut field, chunk_x, chunk_y) {
+            return Err(format!(
+                "Chunk ({}, {}) can not fit its neighbors.",
+                chunk_x, chunk_y
+            ));
+        }
+
+        let mut rng = Isaac64Rng::from_seed(&[self.seed, chunk_x as u64, chunk_y as u64]);
+
+        for _ in 0..self.max_attempts {
+            if let Some(indices) = field.clone().solve(&mut rng) {
+                return Ok(indices);
+            }
+        }
+
+        Err(format!(
+            "Chunk ({}, {}) failed to generate after {} attempts.",
+            chunk_x, chunk_y, self.max_attempts
+        ))
+    }
+
+    fn constrain_borders(&self, field: &mut Field, chunk_x: i64, chunk_y: i64) -> bool {
+        let width = self.chunk_width();
+        let height = self.chunk_height();
+
+        let origin_x = chunk_x * width as i64;
+        let origin_y = chunk_y * height as i64;
+
+        for y in 0..height {
+            for x in 0..width {
+                if x != 0 && y != 0 && x != width - 1 && y != height - 1 {
+                    continue;
+                }
+
+                for direction in &Direction::ALL_DIRECTIONS {
+                    let (delta_x, delta_y) = direction.offset();
+                    let test_x = x as i64 + delta_x as i64;
+                    let test_y = y as i64 + delta_y as i64;
+
+                    let inside = test_x >= 0 && test_y >= 0 && test_x < width as i64
+                        && test_y < height as i64;
+
+                    if inside {
+                        continue;
+                    }
+
+                    if let Some(neighbor) =
+                        self.generated_cell(origin_x + test_x, origin_y + test_y)
+                    {
+                        if neighbor != MASKED && field.is_active(x, y)
+                            && !field.restrict_neighbor(x, y, *direction, neighbor)
+                        {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
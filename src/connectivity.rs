@@ -0,0 +1,10 @@
+// Global constraints on how the connections described by each potential's
+// Boundary link the cells of the field together.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Connectivity {
+    // Every cell with a connection must be part of a single connected
+    // component.
+    AllConnected,
+    // The two cells, given as (x, y), must be connected to each other.
+    Connected((usize, usize), (usize, usize)),
+}
@@ -0,0 +1,106 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Limit {
+    Cells(usize),
+    // Fraction of the active cells of the field.
+    Fraction(f32),
+}
+
+// Limits how many cells of the field may collapse to any of a group of
+// potentials.
+#[derive(Debug, Clone)]
+pub struct CountConstraint {
+    potentials: Vec<usize>,
+    min: Option<Limit>,
+    max: Option<Limit>,
+}
+
+impl CountConstraint {
+    pub fn exactly(potentials: &[usize], count: usize) -> CountConstraint {
+        CountConstraint::between(potentials, count, count)
+    }
+
+    pub fn at_least(potentials: &[usize], count: usize) -> CountConstraint {
+        CountConstraint::build(potentials, Some(Limit::Cells(count)), None)
+    }
+
+    pub fn at_most(potentials: &[usize], count: usize) -> CountConstraint {
+        CountConstraint::build(potentials, None, Some(Limit::Cells(count)))
+    }
+
+    pub fn between(potentials: &[usize], min: usize, max: usize) -> CountConstraint {
+        CountConstraint::build(
+            potentials,
+            Some(Limit::Cells(min)),
+            Some(Limit::Cells(max)),
+        )
+    }
+
+    pub fn at_least_fraction(potentials: &[usize], fraction: f32) -> CountConstraint {
+        CountConstraint::build(potentials, Some(Limit::Fraction(fraction)), None)
+    }
+
+    pub fn at_most_fraction(potentials: &[usize], fraction: f32) -> CountConstraint {
+        CountConstraint::build(potentials, None, Some(Limit::Fraction(fraction)))
+    }
+
+    fn build(potentials: &[usize], min: Option<Limit>, max: Option<Limit>) -> CountConstraint {
+        CountConstraint {
+            potentials: potentials.to_vec(),
+            min,
+            max,
+        }
+    }
+
+    pub fn potentials(&self) -> &[usize] {
+        &self.potentials
+    }
+
+    pub fn contains(&self, potential_index: usize) -> bool {
+        self.potentials.contains(&potential_index)
+    }
+
+    // The minimum number of cells, rounding fractions up.
+    pub fn min_cells(&self, num_cells: usize) -> usize {
+        match self.min {
+            None => 0,
+            Some(Limit::Cells(count)) => count,
+            Some(Limit::Fraction(fraction)) => (fraction * num_cells as f32).ceil() as usize,
+        }
+    }
+
+    // The maximum number of cells, rounding fractions down.
+    pub fn max_cells(&self, num_cells: usize) -> usize {
+        match self.max {
+            None => num_cells,
+            Some(Limit::Cells(count)) => count,
+            Some(Limit::Fraction(fraction)) => (fraction * num_cells as f32).floor() as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn count_limits() {
+        let exact = CountConstraint::exactly(&[0, 2], 3);
+        assert_eq!(exact.min_cells(10), 3);
+        assert_eq!(exact.max_cells(10), 3);
+        assert!(exact.contains(2));
+        assert!(!exact.contains(1));
+
+        let most = CountConstraint::at_most(&[1], 5);
+        assert_eq!(most.min_cells(10), 0);
+        assert_eq!(most.max_cells(10), 5);
+
+        let fraction = CountConstraint::at_least_fraction(&[1], 0.25);
+        assert_eq!(fraction.min_cells(10), 3);
+        assert_eq!(fraction.max_cells(10), 10);
+
+        let fraction = CountConstraint::at_most_fraction(&[1], 0.25);
+        assert_eq!(fraction.min_cells(10), 0);
+        assert_eq!(fraction.max_cells(10), 2);
+    }
+}
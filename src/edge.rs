@@ -0,0 +1,86 @@
+use boundary::Direction;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Side {
+    pub const ALL_SIDES: [Side; 4] = [Side::North, Side::East, Side::South, Side::West];
+
+    // The directions that point out of the field from this side.
+    pub fn outward_directions(self) -> [Direction; 3] {
+        match self {
+            Side::North => [Direction::NorthWest, Direction::North, Direction::NorthEast],
+            Side::East => [Direction::NorthEast, Direction::East, Direction::SouthEast],
+            Side::South => [Direction::SouthEast, Direction::South, Direction::SouthWest],
+            Side::West => [Direction::SouthWest, Direction::West, Direction::NorthWest],
+        }
+    }
+
+    fn to_index(self) -> usize {
+        match self {
+            Side::North => 0,
+            Side::East => 1,
+            Side::South => 2,
+            Side::West => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgePolicy {
+    // No potential may connect out of the field.
+    Closed,
+    // Every potential must connect out of the field.
+    Open,
+    // Anything goes.
+    Free,
+    // Only the listed potentials may be placed along the edge.
+    OneOf(Vec<usize>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Edges {
+    policies: [EdgePolicy; 4],
+    entrances: Vec<(Side, usize)>,
+}
+
+impl Edges {
+    pub fn new(policy: EdgePolicy) -> Edges {
+        Edges {
+            policies: [policy.clone(), policy.clone(), policy.clone(), policy],
+            entrances: Vec::new(),
+        }
+    }
+
+    pub fn with_side(mut self, side: Side, policy: EdgePolicy) -> Edges {
+        self.policies[side.to_index()] = policy;
+        self
+    }
+
+    // Marks the cell at offset along side as an entrance, which must connect
+    // out of the field regardless of the side's policy.  The offset is the x
+    // coordinate for the north and south sides, and the y coordinate for the
+    // east and west sides.  Field::apply_edges fails if the offset is past the
+    // end of the side.
+    pub fn with_entrance(mut self, side: Side, offset: usize) -> Edges {
+        self.entrances.push((side, offset));
+        self
+    }
+
+    pub fn policy(&self, side: Side) -> &EdgePolicy {
+        &self.policies[side.to_index()]
+    }
+
+    pub fn is_entrance(&self, side: Side, offset: usize) -> bool {
+        self.entrances.contains(&(side, offset))
+    }
+
+    pub fn entrances(&self) -> &[(Side, usize)] {
+        &self.entrances
+    }
+}
@@ -1,9 +1,18 @@
 
 use boundary::Boundary;
+use field::Field;
+use render::{Renderer, TextEntry, TextRenderer};
 
 pub trait Entry {
     fn weight(&self) -> f32;
     fn boundary(&self) -> &Boundary;
+
+    // The number of cells the entry covers, as (width, height).  The
+    // boundary only applies to the outer edges of the footprint, and the
+    // weight is shared between the cells.
+    fn footprint(&self) -> (usize, usize) {
+        (1, 1)
+    }
 }
 
 pub struct CharacterEntry {
@@ -24,6 +33,12 @@ impl CharacterEntry {
     }
 }
 
+impl TextEntry for CharacterEntry {
+    fn text(&self) -> String {
+        self.character.to_string()
+    }
+}
+
 impl Entry for CharacterEntry {
     fn weight(&self) -> f32 {
         self.weight
@@ -35,20 +50,60 @@ impl Entry for CharacterEntry {
 }
 
 pub fn make_string(potentials: &[CharacterEntry], indices: &[Vec<usize>]) -> String {
-    let mut result = String::new();
-
-    for row in indices {
-        for i in row {
-            if *i < potentials.len() {
-                result.push(potentials[*i].character);
-            } else if *i == potentials.len() {
-                result.push('?');
+    TextRenderer::default().render(potentials, indices)
+}
+
+// The inverse of make_string: reads each character back into its potential
+// index, with '?' marking cells that are not known yet.
+pub fn parse_string(
+    potentials: &[CharacterEntry],
+    text: &str,
+) -> Result<Vec<Vec<Option<usize>>>, String> {
+    let mut result = Vec::new();
+
+    for (y, line) in text.lines().enumerate() {
+        let mut row = Vec::new();
+
+        for (x, c) in line.chars().enumerate() {
+            if c == '?' {
+                row.push(None);
+            } else if let Some(i) = potentials.iter().position(|p| p.character == c) {
+                row.push(Some(i));
             } else {
-                result.push('!');
+                return Err(format!(
+                    "Found unknown character '{}' at ({}, {}) in parse_string.",
+                    c, x, y
+                ));
             }
         }
-        result.push('\n');
+
+        result.push(row);
+    }
+
+    Ok(result)
+}
+
+// Pins every known cell of text into the field, propagating as it goes.  The
+// text must have exactly one line per row and one character per cell.
+pub fn load_string(
+    field: &mut Field,
+    potentials: &[CharacterEntry],
+    text: &str,
+) -> Result<(), String> {
+    let cells = parse_string(potentials, text)?;
+
+    if cells.len() != field.height() || cells.iter().any(|row| row.len() != field.width()) {
+        return Err(format!(
+            "The text is the wrong size for a {} by {} field.",
+            field.width(),
+            field.height()
+        ));
     }
 
-    result
+    field.force_potentials(&cells).map_err(|(x, y)| {
+        format!(
+            "Cell ({}, {}) conflicts with the cells loaded before it.",
+            x, y
+        )
+    })
 }
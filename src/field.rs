@@ -1,14 +1,28 @@
-
-use std::f32;
-use std::usize;
+use std::sync::Arc;
 
 use rand::Rng;
-use rand::distributions::{IndependentSample, Range};
 
 use changequeue::ChangeQueue;
 use containerutils::extract_two_elements;
 use boundary::{Boundary, Direction};
+use connectivity::Connectivity;
+use count::CountConstraint;
+use edge::{EdgePolicy, Edges, Side};
 use entry::Entry;
+use footprint::{parts_fit, Part};
+use observer::{FieldObserver, NullObserver};
+use selector::{CellSelector, MinEntropy};
+use spacing::SpacingConstraint;
+use symmetry::Symmetry;
+use chooser::{ValueChooser, WeightedRandom};
+
+// Value reported by render and render_partial for cells that have been masked
+// out of the field.
+pub const MASKED: usize = usize::MAX - 1;
+
+// Stands in for the point index of a step that is a checkpoint rather than a
+// choice.
+const CHECKPOINT: usize = usize::MAX;
 
 #[derive(Clone, Debug)]
 struct FieldPoint {
@@ -40,7 +54,7 @@ impl FieldPoint {
             assert!(self.num_allowed > 0);
             self.num_allowed -= 1;
 
-            assert!(self.invalidate_step[index] == None);
+            assert!(self.invalidate_step[index].is_none());
             self.invalidate_step[index] = Some(step);
             self.max_invalidate_step = Some(step);
         }
@@ -52,7 +66,7 @@ impl FieldPoint {
         }
 
         for invalidate in &mut self.invalidate_step {
-            if *invalidate == None {
+            if invalidate.is_none() {
                 *invalidate = Some(step);
             }
         }
@@ -64,28 +78,6 @@ impl FieldPoint {
         self.max_invalidate_step = Some(step);
     }
 
-    fn choose<R: Rng>(&self, weights: &[PointWeight], mut rng: &mut R) -> Option<usize> {
-        assert!(self.num_allowed > 0);
-
-        let normalized_range = Range::new(0.0, 1.0f32);
-        let mut total_weight = 0.0;
-        let mut current_choice = None;
-
-        for (index, allow) in self.allowed.iter().enumerate() {
-            if *allow {
-                let current_weight = weights[index].weight;
-
-                total_weight += current_weight;
-
-                if normalized_range.ind_sample(&mut rng) * total_weight < current_weight {
-                    current_choice = Some(index);
-                }
-            }
-        }
-
-        current_choice
-    }
-
     fn revert_to(&mut self, step: usize) {
         if self.max_invalidate_step > Some(step) {
             let num_potentials = self.allowed.len();
@@ -107,6 +99,10 @@ impl FieldPoint {
         }
     }
 
+    fn allows_any(&self, potentials: &[usize]) -> bool {
+        potentials.iter().any(|p| self.allowed[*p])
+    }
+
     fn extract_selection(&self) -> Option<usize> {
         if self.num_allowed == 1 {
             for (i, allow) in self.allowed.iter().enumerate() {
@@ -116,343 +112,1456 @@ impl FieldPoint {
             }
         }
 
-        None
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PointWeight {
+    weight: f32,
+    entropic_element: f32,
+}
+
+impl PointWeight {
+    pub fn new(weight: f32) -> PointWeight {
+        PointWeight {
+            weight,
+            entropic_element: weight * weight.ln(),
+        }
+    }
+}
+
+fn measure_entropy(point: &FieldPoint, weights: &[PointWeight]) -> f32 {
+    let mut total_weight = 0.0f32;
+    let mut total_component = 0.0f32;
+
+    for (index, allowed) in point.allowed.iter().enumerate() {
+        if *allowed {
+            total_weight += weights[index].weight;
+            total_component += weights[index].entropic_element;
+        }
+    }
+
+    total_weight.ln() - (total_component / total_weight)
+}
+
+#[derive(Clone, Debug)]
+pub struct Field {
+    num_potentials: usize,
+    num_entries: usize,
+
+    parts: Vec<Part>,
+    boundaries: Vec<Boundary>,
+    weights: Vec<PointWeight>,
+
+    width: usize,
+    height: usize,
+
+    points: Vec<FieldPoint>,
+    active: Vec<bool>,
+    steps: Vec<(usize, usize)>, // (point_index, potential_index)
+
+    allow_backtracking: bool,
+
+    count_constraints: Vec<CountConstraint>,
+    connectivity: Vec<Connectivity>,
+    spacing: Vec<SpacingConstraint>,
+    preferences: Vec<(usize, Direction, usize, f32)>, // (potential, direction, neighbor, weight)
+    targets: Vec<(usize, f32)>,                       // (potential, fraction)
+    symmetries: Vec<(Symmetry, Vec<Option<usize>>)>,  // (symmetry, image of each potential)
+
+    selector: Arc<dyn CellSelector>,
+    chooser: Arc<dyn ValueChooser>,
+}
+
+impl Field {
+    pub fn new<E: Entry>(potentials: &[E], width: usize, height: usize) -> Field {
+        let footprints: Vec<(usize, usize)> =
+            potentials.iter().map(|entry| entry.footprint()).collect();
+        let parts = Part::split(&footprints);
+
+        let num_potentials = parts.len();
+        let mut boundaries = Vec::with_capacity(num_potentials);
+        let mut weights = Vec::with_capacity(num_potentials);
+
+        // An entry's weight is spread over its parts, so it covers cells in
+        // proportion to its weight, the same as a single cell entry does.
+        for part in &parts {
+            let entry = &potentials[part.entry];
+            boundaries.push(entry.boundary().clone());
+            weights.push(PointWeight::new(
+                entry.weight() / (part.width * part.height) as f32,
+            ));
+        }
+
+        let mut prototype_fieldpoint = FieldPoint::new(num_potentials);
+
+        for (potential_index, part) in parts.iter().enumerate() {
+            if potentials[part.entry].weight() <= 0.0 {
+                prototype_fieldpoint.invalidate(potential_index, 0);
+            }
+        }
+
+        let num_points = width * height;
+
+        let mut points = Vec::new();
+        points.resize(num_points, prototype_fieldpoint);
+
+        // There should be no more than num_points steps to solve it!
+        let steps = Vec::with_capacity(num_points);
+
+        let mut field = Field {
+            num_potentials,
+            num_entries: potentials.len(),
+            parts,
+            boundaries,
+            weights,
+            width,
+            height,
+            points,
+            active: vec![true; num_points],
+            steps,
+            allow_backtracking: false,
+            count_constraints: Vec::new(),
+            connectivity: Vec::new(),
+            spacing: Vec::new(),
+            preferences: Vec::new(),
+            targets: Vec::new(),
+            symmetries: Vec::new(),
+            selector: Arc::new(MinEntropy),
+            chooser: Arc::new(WeightedRandom),
+        };
+
+        // A field too small for its footprints shows up as a contradiction
+        // in render_partial.
+        field.prune_footprints();
+        field
+    }
+
+    pub fn allow_backtracking(self) -> Field {
+        Field {
+            allow_backtracking: true,
+            ..self
+        }
+    }
+
+    pub fn with_count_constraint(mut self, constraint: CountConstraint) -> Field {
+        self.count_constraints.push(constraint);
+        self
+    }
+
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Field {
+        self.connectivity.push(connectivity);
+        self
+    }
+
+    // Scales the weight of potential_index by weight whenever the cell in
+    // direction has already collapsed to neighbor_potential.  Preferences
+    // never forbid anything, they only make a choice more or less likely.
+    pub fn with_preference(
+        mut self,
+        potential_index: usize,
+        direction: Direction,
+        neighbor_potential: usize,
+        weight: f32,
+    ) -> Field {
+        assert!(weight > 0.0, "Preference weights must be positive.");
+
+        self.preferences
+            .push((potential_index, direction, neighbor_potential, weight));
+        self
+    }
+
+    // Steers the choices made by step so that about fraction of the cells end
+    // up as potential_index.  Once any target is set, the targets replace the
+    // weights of their potentials, and the other potentials share what is
+    // left in proportion to their weights.  Calling this again for the same
+    // potential replaces its target.
+    pub fn with_target_frequency(mut self, potential_index: usize, fraction: f32) -> Field {
+        let fraction = fraction.clamp(0.0, 1.0);
+
+        match self.targets.iter().position(|&(p, _)| p == potential_index) {
+            Some(i) => self.targets[i].1 = fraction,
+            None => self.targets.push((potential_index, fraction)),
+        }
+
+        self
+    }
+
+    // Ties every cell to its image under symmetry.  A potential's image is the
+    // potential with the transformed boundary, and potentials without one
+    // are removed.  Cells that are their own image may only hold potentials
+    // that are their own image.  Fails if that leaves a cell with nothing
+    // allowed.
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Result<Field, String> {
+        let images: Vec<Option<usize>> = (0..self.num_potentials)
+            .map(|potential_index| self.symmetric_image(symmetry, potential_index))
+            .collect();
+
+        let mut changes = ChangeQueue::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.is_active(x, y) {
+                    continue;
+                }
+
+                let point_index = generate_index(x, y, self.width);
+                let is_center = symmetry.cell(x, y, self.width, self.height) == (x, y);
+
+                for (potential_index, image) in images.iter().enumerate() {
+                    if image.is_none() || (is_center && *image != Some(potential_index)) {
+                        self.points[point_index].invalidate(potential_index, 0);
+                        changes.add((x, y));
+                    }
+                }
+
+                if self.points[point_index].num_allowed == 0 {
+                    return Err(format!(
+                        "Field::with_symmetry() leaves nothing allowed at ({}, {}).",
+                        x, y
+                    ));
+                }
+            }
+        }
+
+        self.symmetries.push((symmetry, images));
+
+        if self.propagate(changes) {
+            Ok(self)
+        } else {
+            Err(format!(
+                "Field::with_symmetry() leaves the field unsolvable under {:?} symmetry.",
+                symmetry
+            ))
+        }
+    }
+
+    // The potential with the transformed boundary, and for a multi-cell
+    // entry the transformed position in the footprint.  Every part of an
+    // entry shares its boundary, so the whole footprint maps to the same
+    // entry, preferring the entry itself when it is symmetric.
+    fn symmetric_image(&self, symmetry: Symmetry, potential_index: usize) -> Option<usize> {
+        let part = &self.parts[potential_index];
+        let boundary = symmetry.boundary(&self.boundaries[potential_index]);
+        let position = symmetry.cell(part.x, part.y, part.width, part.height);
+
+        let is_image = |image_index: &usize| {
+            let image = &self.parts[*image_index];
+
+            image.width == part.width && image.height == part.height
+                && (image.x, image.y) == position
+                && self.boundaries[*image_index] == boundary
+        };
+
+        (0..self.num_potentials)
+            .filter(&is_image)
+            .find(|image_index| self.parts[*image_index].entry == part.entry)
+            .or_else(|| (0..self.num_potentials).find(&is_image))
+    }
+
+    pub fn with_spacing(mut self, spacing: SpacingConstraint) -> Field {
+        self.spacing.push(spacing);
+        self
+    }
+
+    pub fn with_selector<S: CellSelector + 'static>(self, selector: S) -> Field {
+        Field {
+            selector: Arc::new(selector),
+            ..self
+        }
+    }
+
+    pub fn with_chooser<C: ValueChooser + 'static>(self, chooser: C) -> Field {
+        Field {
+            chooser: Arc::new(chooser),
+            ..self
+        }
+    }
+
+    pub fn with_mask(self, mask: &[Vec<bool>]) -> Result<Field, String> {
+        if !has_shape(mask, self.width, self.height) {
+            return Err(format!(
+                "Field::with_mask() is incorrect.  Expected a mask of {}x{} cells.",
+                self.width, self.height
+            ));
+        }
+
+        let active = mask.iter()
+            .flat_map(|row| row.iter().cloned())
+            .collect();
+
+        let mut field = Field { active, ..self };
+
+        if !field.prune_footprints() {
+            return Err(String::from(
+                "Field::with_mask() leaves a cell with nothing allowed.",
+            ));
+        }
+
+        Ok(field)
+    }
+
+    // Removes every part whose footprint would not fit entirely on active
+    // cells, and propagates the removals to the neighbors.  Returns false if
+    // that leaves a cell with nothing allowed.
+    fn prune_footprints(&mut self) -> bool {
+        let mut changes = ChangeQueue::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.is_active(x, y) {
+                    continue;
+                }
+
+                let point_index = generate_index(x, y, self.width);
+
+                for potential_index in 0..self.num_potentials {
+                    let part = self.parts[potential_index];
+
+                    if part.width == 1 && part.height == 1 {
+                        continue;
+                    }
+
+                    let fits = x >= part.x && y >= part.y
+                        && (0..part.height).all(|fy| {
+                            (0..part.width)
+                                .all(|fx| self.is_active(x - part.x + fx, y - part.y + fy))
+                        });
+
+                    if !fits {
+                        self.points[point_index].invalidate(potential_index, 0);
+                        changes.add((x, y));
+                    }
+                }
+            }
+        }
+
+        self.propagate(changes)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // The number of potentials each cell starts with.  This is larger than
+    // the number of entries if any entry covers more than one cell.
+    pub fn num_potentials(&self) -> usize {
+        self.num_potentials
+    }
+
+    pub fn num_entries(&self) -> usize {
+        self.num_entries
+    }
+
+    pub fn part(&self, potential_index: usize) -> &Part {
+        &self.parts[potential_index]
+    }
+
+    // Converts the potentials from render or render_partial to the entries
+    // they are a part of, so they can be displayed with those entries.
+    pub fn to_entries(&self, indices: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        indices
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|i| {
+                        if *i < self.num_potentials {
+                            self.parts[*i].entry
+                        } else if *i == self.num_potentials {
+                            self.num_entries
+                        } else {
+                            *i
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Returns 0 for cells that are masked out or outside of the field.
+    pub fn num_allowed(&self, x: usize, y: usize) -> usize {
+        if self.is_active(x, y) {
+            self.points[generate_index(x, y, self.width)].num_allowed
+        } else {
+            0
+        }
+    }
+
+    pub fn is_allowed(&self, x: usize, y: usize, potential_index: usize) -> bool {
+        self.is_active(x, y)
+            && potential_index < self.num_potentials
+            && self.points[generate_index(x, y, self.width)].allowed[potential_index]
+    }
+
+    pub fn allowed_potentials(&self, x: usize, y: usize) -> Vec<usize> {
+        (0..self.num_potentials)
+            .filter(|potential_index| self.is_allowed(x, y, *potential_index))
+            .collect()
+    }
+
+    pub fn weight(&self, potential_index: usize) -> f32 {
+        self.weights[potential_index].weight
+    }
+
+    // The weight of potential_index at the cell, including the preferences
+    // for the neighbors that have already collapsed.
+    pub fn weight_at(&self, x: usize, y: usize, potential_index: usize) -> f32 {
+        let histogram = self.target_histogram();
+
+        self.weight_with_histogram(x, y, potential_index, histogram.as_deref())
+    }
+
+    // The weight_at of every potential the cell allows, as (potential_index,
+    // weight).  The collapsed cells are only counted once for all of them.
+    pub fn weights_at(&self, x: usize, y: usize) -> Vec<(usize, f32)> {
+        let histogram = self.target_histogram();
+
+        self.allowed_potentials(x, y)
+            .into_iter()
+            .map(|potential_index| {
+                (
+                    potential_index,
+                    self.weight_with_histogram(x, y, potential_index, histogram.as_deref()),
+                )
+            })
+            .collect()
+    }
+
+    // The histogram, when there are targets that need it.
+    fn target_histogram(&self) -> Option<Vec<usize>> {
+        if self.targets.is_empty() {
+            None
+        } else {
+            Some(self.histogram())
+        }
+    }
+
+    fn weight_with_histogram(
+        &self,
+        x: usize,
+        y: usize,
+        potential_index: usize,
+        histogram: Option<&[usize]>,
+    ) -> f32 {
+        let mut weight = match histogram {
+            Some(histogram) => self.target_weight(potential_index, histogram),
+            None => self.weight(potential_index),
+        };
+
+        if self.preferences.is_empty() {
+            return weight;
+        }
+
+        for direction in &Direction::ALL_DIRECTIONS {
+            let neighbor_potential = self.adjacent(x, y, *direction).and_then(|(test_x, test_y)| {
+                self.points[generate_index(test_x, test_y, self.width)].extract_selection()
+            });
+
+            if let Some(neighbor_potential) = neighbor_potential {
+                for &(p, d, n, preference) in &self.preferences {
+                    if p == potential_index && d == *direction && n == neighbor_potential {
+                        weight *= preference;
+                    }
+                }
+            }
+        }
+
+        weight
+    }
+
+    // The number of collapsed cells holding each potential.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.num_potentials];
+
+        for (point_index, point) in self.points.iter().enumerate() {
+            if self.active[point_index] {
+                if let Some(potential_index) = point.extract_selection() {
+                    counts[potential_index] += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
+    // For every target frequency, how far the collapsed cells are from it as
+    // (potential_index, observed fraction - target fraction).
+    pub fn target_deviation(&self) -> Vec<(usize, f32)> {
+        let histogram = self.histogram();
+        let num_decided: usize = histogram.iter().sum();
+
+        self.targets
+            .iter()
+            .map(|&(potential_index, target)| {
+                let observed = if num_decided > 0 {
+                    histogram[potential_index] as f32 / num_decided as f32
+                } else {
+                    0.0
+                };
+
+                (potential_index, observed - target)
+            })
+            .collect()
+    }
+
+    // The fraction of cells potential_index should end up in, from the targets
+    // and the weights of the potentials without one.
+    fn target_fraction(&self, potential_index: usize) -> f32 {
+        if let Some(&(_, target)) = self.targets.iter().find(|&&(p, _)| p == potential_index) {
+            return target;
+        }
+
+        let untargeted = |p: &usize| !self.targets.iter().any(|&(target_p, _)| target_p == *p);
+        let total_weight: f32 = (0..self.num_potentials)
+            .filter(untargeted)
+            .map(|p| self.weight(p).max(0.0))
+            .sum();
+        let remaining = 1.0 - self.targets.iter().map(|&(_, target)| target).sum::<f32>();
+
+        if total_weight > 0.0 {
+            remaining.max(0.0) * self.weight(potential_index).max(0.0) / total_weight
+        } else {
+            0.0
+        }
+    }
+
+    // Scales the target fraction up exponentially with how many cells the
+    // potential is behind where it should be for the number of cells
+    // collapsed so far, and down by how many it is ahead.  A potential left
+    // without a share still gets a tiny weight, so a cell that allows
+    // nothing else can be collapsed.
+    fn target_weight(&self, potential_index: usize, histogram: &[usize]) -> f32 {
+        let fraction = self.target_fraction(potential_index);
+        let num_decided: usize = histogram.iter().sum();
+        let wanted = fraction * (num_decided + 1) as f32;
+
+        let behind = wanted - histogram[potential_index] as f32;
+
+        fraction.max(1e-6) * behind.clamp(-20.0, 20.0).exp()
+    }
+
+    pub fn boundary(&self, potential_index: usize) -> &Boundary {
+        &self.boundaries[potential_index]
+    }
+
+    // The neighboring cell in direction, if it exists and is not masked out.
+    pub fn adjacent(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        if self.is_active(x, y) {
+            self.build_delta(x, y, direction)
+        } else {
+            None
+        }
+    }
+
+    // The Shannon entropy of the weights of the cell's allowed potentials.
+    // Returns 0 for cells that are masked out or outside of the field.
+    pub fn entropy(&self, x: usize, y: usize) -> f32 {
+        if self.is_active(x, y) {
+            measure_entropy(&self.points[generate_index(x, y, self.width)], &self.weights)
+        } else {
+            0.0
+        }
+    }
+
+    // Returns false if the cell is masked out, or outside of the field entirely.
+    pub fn is_active(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.active[generate_index(x, y, self.width)]
+    }
+
+    pub fn close_edges(&mut self) -> bool {
+        self.close_missing_neighbors(|field, x, y, direction| {
+            field.neighbor(x, y, direction).is_none()
+        })
+    }
+
+    // Like close_edges, but closes the cells bordering the masked out cells
+    // instead of the outside of the field.
+    pub fn close_mask_edges(&mut self) -> bool {
+        self.close_missing_neighbors(|field, x, y, direction| {
+            match field.neighbor(x, y, direction) {
+                Some((neighbor_x, neighbor_y)) => !field.is_active(neighbor_x, neighbor_y),
+                None => false,
+            }
+        })
+    }
+
+    fn close_missing_neighbors<F>(&mut self, is_missing: F) -> bool
+    where
+        F: Fn(&Field, usize, usize, Direction) -> bool,
+    {
+        let mut changes = ChangeQueue::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.is_active(x, y) {
+                    continue;
+                }
+
+                for direction in &Direction::ALL_DIRECTIONS {
+                    if !is_missing(self, x, y, *direction) {
+                        continue;
+                    }
+
+                    // Do not allow a potential next to a missing neighbor if it
+                    // requires a connection in that neighbor's direction.
+                    if !self.keep_potentials(
+                        x,
+                        y,
+                        |_, boundary| !boundary.requires(*direction),
+                        &mut changes,
+                    ) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        self.propagate(changes)
+    }
+
+    pub fn force_potential(&mut self, x: usize, y: usize, potential_index: usize) -> bool {
+        if !self.is_active(x, y) {
+            return false;
+        }
+
+        let point_index = generate_index(x, y, self.width);
+
+        {
+            let point = &mut self.points[point_index];
+
+            // Forcing a potential that was already ruled out would silently
+            // undo whatever ruled it out.
+            if !point.allowed[potential_index] {
+                return false;
+            }
+
+            point.force(potential_index, self.steps.len());
+        }
+
+        let mut changes = ChangeQueue::new();
+        changes.add((x, y));
+
+        self.propagate(changes)
+    }
+
+    // Forces every known cell, in reading order, with the same layout as the
+    // result of render.  Returns the first cell that could not be forced.
+    pub fn force_potentials(&mut self, cells: &[Vec<Option<usize>>]) -> Result<(), (usize, usize)> {
+        for (y, row) in cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(potential_index) = *cell {
+                    if !self.force_potential(x, y, potential_index) {
+                        return Err((x, y));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Removes every potential not in allowed from the cell.
+    pub fn restrict(&mut self, x: usize, y: usize, allowed: &[usize]) -> bool {
+        self.is_active(x, y) && self.restrict_region(x, y, 1, 1, allowed)
+    }
+
+    // Like restrict, but reports the potentials removed by propagation to
+    // observer.
+    pub fn restrict_observed<O: FieldObserver>(
+        &mut self,
+        x: usize,
+        y: usize,
+        allowed: &[usize],
+        observer: &mut O,
+    ) -> bool {
+        if !self.is_active(x, y) {
+            return false;
+        }
+
+        let mut changes = ChangeQueue::new();
+
+        self.keep_potentials(
+            x,
+            y,
+            |potential_index, _| allowed.contains(&potential_index),
+            &mut changes,
+        ) && self.propagate_observed(changes, observer)
+    }
+
+    // Removes every potential not in allowed from the cells of the rectangle.
+    // Masked out cells inside the rectangle are left alone.
+    pub fn restrict_region(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        allowed: &[usize],
+    ) -> bool {
+        let mut changes = ChangeQueue::new();
+
+        for region_y in y..y + height {
+            for region_x in x..x + width {
+                if !self.is_active(region_x, region_y) {
+                    continue;
+                }
+
+                if !self.keep_potentials(
+                    region_x,
+                    region_y,
+                    |potential_index, _| allowed.contains(&potential_index),
+                    &mut changes,
+                ) {
+                    return false;
+                }
+            }
+        }
+
+        self.propagate(changes)
+    }
+
+    // Removes every potential from the cell that would not fit next to a cell
+    // outside of the field, in direction, holding neighbor_potential.
+    pub fn restrict_neighbor(
+        &mut self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+        neighbor_potential: usize,
+    ) -> bool {
+        if !self.is_active(x, y) {
+            return false;
+        }
+
+        let neighbor_part = self.parts[neighbor_potential];
+        let neighbor = self.boundaries[neighbor_potential].clone();
+        let parts = self.parts.clone();
+        let mut changes = ChangeQueue::new();
+
+        self.keep_potentials(
+            x,
+            y,
+            |potential_index, boundary| {
+                parts_fit(
+                    &parts[potential_index],
+                    boundary,
+                    &neighbor_part,
+                    &neighbor,
+                    direction,
+                )
+            },
+            &mut changes,
+        ) && self.propagate(changes)
+    }
+
+    pub fn ban(&mut self, x: usize, y: usize, potential_index: usize) -> bool {
+        if !self.is_active(x, y) {
+            return false;
+        }
+
+        let mut changes = ChangeQueue::new();
+
+        invalidate_and_queue(
+            x,
+            y,
+            self.width,
+            potential_index,
+            &mut self.points,
+            self.steps.len(),
+            &mut changes,
+        ) && self.propagate(changes)
+    }
+
+    // Fails if an entrance is past the end of its side or masked out, or if
+    // the edges leave the field unsolvable.
+    pub fn apply_edges(&mut self, edges: &Edges) -> Result<(), String> {
+        for &(side, offset) in edges.entrances() {
+            let cells = self.edge_cells(side);
+
+            match cells.get(offset) {
+                None => {
+                    return Err(format!(
+                        "Entrance {} on the {:?} side is past the end of the side, which has {} cells.",
+                        offset,
+                        side,
+                        cells.len()
+                    ))
+                }
+                Some(&(x, y)) if !self.is_active(x, y) => {
+                    return Err(format!(
+                        "Entrance {} on the {:?} side is masked out.",
+                        offset, side
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        let mut changes = ChangeQueue::new();
+
+        for side in &Side::ALL_SIDES {
+            let outward = side.outward_directions();
+            let connects_out =
+                |boundary: &Boundary| outward.iter().any(|direction| boundary.requires(*direction));
+
+            for (offset, (x, y)) in self.edge_cells(*side).into_iter().enumerate() {
+                if !self.is_active(x, y) {
+                    continue;
+                }
+
+                let kept = if edges.is_entrance(*side, offset) {
+                    self.keep_potentials(x, y, |_, boundary| connects_out(boundary), &mut changes)
+                } else {
+                    match *edges.policy(*side) {
+                        EdgePolicy::Closed => self.keep_potentials(
+                            x,
+                            y,
+                            |_, boundary| !connects_out(boundary),
+                            &mut changes,
+                        ),
+                        EdgePolicy::Open => self.keep_potentials(
+                            x,
+                            y,
+                            |_, boundary| connects_out(boundary),
+                            &mut changes,
+                        ),
+                        EdgePolicy::Free => true,
+                        EdgePolicy::OneOf(ref allowed) => self.keep_potentials(
+                            x,
+                            y,
+                            |potential_index, _| allowed.contains(&potential_index),
+                            &mut changes,
+                        ),
+                    }
+                };
+
+                if !kept {
+                    return Err(String::from("The edges leave the field unsolvable."));
+                }
+            }
+        }
+
+        if self.propagate(changes) {
+            Ok(())
+        } else {
+            Err(String::from("The edges leave the field unsolvable."))
+        }
+    }
+
+    fn edge_cells(&self, side: Side) -> Vec<(usize, usize)> {
+        match side {
+            Side::North => (0..self.width).map(|x| (x, 0)).collect(),
+            Side::East => (0..self.height).map(|y| (self.width - 1, y)).collect(),
+            Side::South => (0..self.width).map(|x| (x, self.height - 1)).collect(),
+            Side::West => (0..self.height).map(|y| (0, y)).collect(),
+        }
+    }
+
+    // Invalidates every potential of the cell that keep rejects, queueing the
+    // cell for propagation.
+    fn keep_potentials<F>(
+        &mut self,
+        x: usize,
+        y: usize,
+        keep: F,
+        changes: &mut ChangeQueue<(usize, usize)>,
+    ) -> bool
+    where
+        F: Fn(usize, &Boundary) -> bool,
+    {
+        let current_step = self.steps.len();
+
+        for potential_index in 0..self.num_potentials {
+            if !keep(potential_index, &self.boundaries[potential_index])
+                && !invalidate_and_queue(
+                    x,
+                    y,
+                    self.width,
+                    potential_index,
+                    &mut self.points,
+                    current_step,
+                    changes,
+                ) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // Reopens the cells inside region so they can be solved again, and locks
+    // every cell outside of it to its potential in indices.  Every step taken
+    // so far is undone first, so the cells inside go back to the potentials
+    // they allowed before solving started.  The locks are a step of their
+    // own, so the next inpaint undoes them too.  Both indices and region are
+    // laid out like the result of render.
+    pub fn inpaint(&mut self, indices: &[Vec<usize>], region: &[Vec<bool>]) -> bool {
+        if !has_shape(indices, self.width, self.height)
+            || !has_shape(region, self.width, self.height)
+        {
+            return false;
+        }
+
+        self.steps.clear();
+        for point in &mut self.points {
+            point.revert_to(0);
+        }
+
+        self.checkpoint();
+
+        let mut changes = ChangeQueue::new();
+        let current_step = self.steps.len();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let potential_index = indices[y][x];
+
+                if region[y][x] || !self.is_active(x, y) {
+                    continue;
+                }
+
+                let point = &mut self.points[generate_index(x, y, self.width)];
+
+                if potential_index >= self.num_potentials || !point.allowed[potential_index] {
+                    return false;
+                }
+
+                point.force(potential_index, current_step);
+                changes.add((x, y));
+            }
+        }
+
+        self.propagate(changes)
+    }
+
+    pub fn inpaint_region(
+        &mut self,
+        indices: &[Vec<usize>],
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> bool {
+        let region: Vec<Vec<bool>> = (0..self.height)
+            .map(|region_y| {
+                (0..self.width)
+                    .map(|region_x| {
+                        region_x >= x && region_x < x + width && region_y >= y
+                            && region_y < y + height
+                    })
+                    .collect()
+            })
+            .collect();
+
+        self.inpaint(indices, &region)
+    }
+
+    pub fn step<R: Rng>(&mut self, rng: &mut R) -> bool {
+        self.step_observed(rng, &mut NullObserver)
+    }
+
+    // Steps until the field is solved, returning the result of render, or
+    // None once a step fails.  A failed step can leave every cell collapsed,
+    // so the field only counts as solved if it renders before a step fails.
+    pub fn solve<R: Rng>(&mut self, rng: &mut R) -> Option<Vec<Vec<usize>>> {
+        self.solve_observed(rng, &mut NullObserver)
+    }
+
+    // Like solve, but reports to observer, and also gives up once the
+    // observer asks to stop.
+    pub fn solve_observed<R: Rng, O: FieldObserver>(
+        &mut self,
+        rng: &mut R,
+        observer: &mut O,
+    ) -> Option<Vec<Vec<usize>>> {
+        loop {
+            if let Some(indices) = self.render() {
+                return Some(indices);
+            }
+
+            if observer.should_stop() || !self.step_observed(rng, observer) {
+                return None;
+            }
+        }
+    }
+
+    pub fn step_observed<R: Rng, O: FieldObserver>(
+        &mut self,
+        mut rng: &mut R,
+        observer: &mut O,
+    ) -> bool {
+        let selector = self.selector.clone();
+        let chooser = self.chooser.clone();
+        let mut possible_best_point = selector
+            .select(self, &mut rng)
+            .map(|(x, y)| generate_index(x, y, self.width));
+
+        loop {
+            match possible_best_point {
+                None => break false,
+                Some(point_index) => {
+                    let (x, y) = generate_coord(point_index, self.width);
+
+                    match chooser.choose(self, x, y, &mut rng) {
+                        Some(choosen_potential) if self.is_allowed(x, y, choosen_potential) => {
+                            self.steps.push((point_index, choosen_potential));
+
+                            self.points[point_index].force(choosen_potential, self.steps.len());
+
+                            observer.on_collapse(x, y, choosen_potential);
+
+                            let mut changes = ChangeQueue::new();
+                            changes.add((x, y));
+
+                            if self.propagate_observed(changes, observer) {
+                                break true;
+                            } else {
+                                possible_best_point = self.revert(observer);
+                            }
+                        }
+
+                        // Choosing nothing, or a potential the cell does not
+                        // allow, fails the step.
+                        _ => break false,
+                    }
+                }
+            }
+        }
+    }
+
+    // Calls visit with every solution of the field, in a fixed order, until
+    // visit returns false.  Returns the number of solutions visited.  Every
+    // branch is explored, so this is only practical for small fields.
+    pub fn enumerate_solutions<F>(&self, mut visit: F) -> usize
+    where
+        F: FnMut(&[Vec<usize>]) -> bool,
+    {
+        let mut field = self.clone();
+        let mut num_visited = 0;
+
+        field.explore(&mut visit, &mut num_visited);
+        num_visited
+    }
+
+    // Up to max_solutions of the field's solutions.
+    pub fn solutions(&self, max_solutions: usize) -> Vec<Vec<Vec<usize>>> {
+        let mut result = Vec::new();
+
+        if max_solutions > 0 {
+            self.enumerate_solutions(|indices| {
+                result.push(indices.to_vec());
+                result.len() < max_solutions
+            });
+        }
+
+        result
     }
-}
 
-struct FoundFieldPoint {
-    point_index: usize,
-    num_encountered: f32,
-    entropy: f32,
-}
+    // The number of solutions, counting no further than cap.
+    pub fn count_solutions(&self, cap: usize) -> usize {
+        if cap == 0 {
+            return 0;
+        }
 
+        let mut count = 0;
 
-impl FoundFieldPoint {
-    fn new(point: &FieldPoint, point_index: usize, weights: &[PointWeight]) -> FoundFieldPoint {
-        FoundFieldPoint {
-            point_index,
-            entropy: measure_entropy(&point, &weights),
-            num_encountered: 1.0,
-        }
+        self.enumerate_solutions(|_| {
+            count += 1;
+            count < cap
+        })
     }
 
-    fn possibly_better<R: Rng>(
-        self,
-        new_point: &FieldPoint,
-        new_point_index: usize,
-        weights: &[PointWeight],
-        rng: &mut R,
-    ) -> FoundFieldPoint {
-        let new_entropy = measure_entropy(&new_point, &weights);
+    // Tries every allowed potential of the undecided cell with the fewest, as
+    // a step that is reverted once its branch has been explored.  Returns
+    // false once visit asks to stop.
+    fn explore<F>(&mut self, visit: &mut F, num_visited: &mut usize) -> bool
+    where
+        F: FnMut(&[Vec<usize>]) -> bool,
+    {
+        let point_index = (0..self.points.len())
+            .filter(|point_index| {
+                self.active[*point_index] && self.points[*point_index].num_allowed > 1
+            })
+            .min_by_key(|point_index| self.points[*point_index].num_allowed);
+
+        let point_index = match point_index {
+            Some(point_index) => point_index,
+            None => {
+                return match self.render() {
+                    Some(indices) => {
+                        *num_visited += 1;
+                        visit(&indices)
+                    }
+                    None => true,
+                };
+            }
+        };
 
-        let epsilon = 1e-6f32;
+        let (x, y) = generate_coord(point_index, self.width);
+        let previous_step = self.steps.len();
 
-        if new_entropy < self.entropy - epsilon {
-            // Always go for the lower entropy.
-            FoundFieldPoint::new(&new_point, new_point_index, &weights)
-        } else if new_entropy > self.entropy + epsilon {
-            // Always reject higher entropy.
-            self
-        } else {
-            // They are nearly equal, use single pass fair selector.fair
-            let num_encountered = self.num_encountered + 1.0;
+        for potential_index in self.allowed_potentials(x, y) {
+            self.steps.push((point_index, potential_index));
+            self.points[point_index].force(potential_index, self.steps.len());
 
-            if rng.gen_range(0.0, self.num_encountered) < 1.0 {
-                FoundFieldPoint {
-                    point_index: new_point_index,
-                    num_encountered,
-                    ..self
-                }
-            } else {
-                FoundFieldPoint {
-                    num_encountered,
-                    ..self
-                }
-            }
-        }
-    }
-}
+            let mut changes = ChangeQueue::new();
+            changes.add((x, y));
 
-#[derive(Clone, Debug)]
-struct PointWeight {
-    weight: f32,
-    entropic_element: f32,
-}
+            let keep_going = !self.propagate(changes) || self.explore(visit, num_visited);
 
-impl PointWeight {
-    pub fn new(weight: f32) -> PointWeight {
-        PointWeight {
-            weight,
-            entropic_element: weight * weight.ln(),
-        }
-    }
-}
+            self.steps.pop();
 
-fn measure_entropy(point: &FieldPoint, weights: &[PointWeight]) -> f32 {
-    let mut total_weight = 0.0f32;
-    let mut total_component = 0.0f32;
+            for point in &mut self.points {
+                point.revert_to(previous_step);
+            }
 
-    for (index, allowed) in point.allowed.iter().enumerate() {
-        if *allowed {
-            total_weight += weights[index].weight;
-            total_component += weights[index].entropic_element;
+            if !keep_going {
+                return false;
+            }
         }
+
+        true
     }
 
-    total_weight.ln() - (total_component / total_weight)
-}
+    fn propagate(&mut self, changes: ChangeQueue<(usize, usize)>) -> bool {
+        self.propagate_observed(changes, &mut NullObserver)
+    }
 
-#[derive(Clone, Debug)]
-pub struct Field {
-    num_potentials: usize,
+    fn propagate_observed<O: FieldObserver>(
+        &mut self,
+        mut changes: ChangeQueue<(usize, usize)>,
+        observer: &mut O,
+    ) -> bool {
+        let current_step = self.steps.len();
 
-    boundaries: Vec<Boundary>,
-    weights: Vec<PointWeight>,
+        loop {
+            while let Some((x, y)) = changes.next() {
+                for direction in &Direction::ALL_DIRECTIONS {
+                    if !self.propagate_direction(
+                        x,
+                        y,
+                        current_step,
+                        *direction,
+                        &mut changes,
+                        observer,
+                    ) {
+                        return false;
+                    }
+                }
 
-    width: usize,
-    height: usize,
+                if !self.propagate_symmetries(x, y, current_step, &mut changes, observer) {
+                    return false;
+                }
 
-    points: Vec<FieldPoint>,
-    steps: Vec<(usize, usize)>, // (point_index, potential_index)
+                if !self.propagate_spacing(x, y, current_step, &mut changes, observer) {
+                    return false;
+                }
+            }
 
-    allow_backtracking: bool,
-}
+            // The global constraints only need to be checked once the local
+            // ones have settled, and may in turn create more local changes.
+            if !self.apply_count_constraints(&mut changes, observer) {
+                return false;
+            }
 
-impl Field {
-    pub fn new<E: Entry>(potentials: &[E], width: usize, height: usize) -> Field {
-        let num_potentials = potentials.len();
-        let mut boundaries = Vec::with_capacity(num_potentials);
-        let mut weights = Vec::with_capacity(num_potentials);
+            if changes.is_empty() {
+                if !self.check_connectivity() {
+                    observer.on_contradiction(None);
+                    return false;
+                }
 
-        for entry in potentials {
-            boundaries.push(entry.boundary().clone());
-            weights.push(PointWeight::new(entry.weight()));
+                return true;
+            }
         }
+    }
 
-        let mut prototype_fieldpoint = FieldPoint::new(num_potentials);
+    // Restricts the images of the cell to the images of its potentials.
+    fn propagate_symmetries<O: FieldObserver>(
+        &mut self,
+        x: usize,
+        y: usize,
+        current_step: usize,
+        changes: &mut ChangeQueue<(usize, usize)>,
+        observer: &mut O,
+    ) -> bool {
+        for &(symmetry, ref images) in &self.symmetries {
+            let (image_x, image_y) = symmetry.cell(x, y, self.width, self.height);
 
-        for (entry_index, entry) in potentials.iter().enumerate() {
-            if entry.weight() <= 0.0 {
-                prototype_fieldpoint.invalidate(entry_index, 0);
+            if (image_x, image_y) == (x, y) || !self.is_active(image_x, image_y) {
+                continue;
             }
-        }
 
-        let num_points = width * height;
+            let point_index = generate_index(x, y, self.width);
+            let image_index = generate_index(image_x, image_y, self.width);
 
-        let mut points = Vec::new();
-        points.resize(num_points, prototype_fieldpoint);
+            if let Some((point, image_point)) =
+                extract_two_elements(&mut self.points, point_index, image_index)
+            {
+                let num_allowed = image_point.num_allowed;
+
+                for potential_index in 0..self.num_potentials {
+                    if image_point.allowed[potential_index]
+                        && !images.iter().enumerate().any(|(source_index, image)| {
+                            point.allowed[source_index] && *image == Some(potential_index)
+                        })
+                    {
+                        image_point.invalidate(potential_index, current_step);
+                        observer.on_invalidate(image_x, image_y, potential_index);
+                    }
+                }
 
-        // There should be no more than num_points steps to solve it!
-        let steps = Vec::with_capacity(num_points);
+                if image_point.num_allowed == 0 {
+                    observer.on_contradiction(Some((image_x, image_y)));
+                    return false;
+                }
 
-        Field {
-            num_potentials,
-            boundaries,
-            weights,
-            width,
-            height,
-            points,
-            steps,
-            allow_backtracking: false,
+                if image_point.num_allowed != num_allowed {
+                    changes.add((image_x, image_y));
+                }
+            }
         }
-    }
 
-    pub fn allow_backtracking(self) -> Field {
-        Field {
-            allow_backtracking: true,
-            ..self
-        }
+        true
     }
 
-    pub fn close_edges(&mut self) -> bool {
-        let mut changes = ChangeQueue::new();
+    // Once the cell has collapsed, bans the potentials its spacing
+    // constraints exclude from every other cell too close to it.
+    fn propagate_spacing<O: FieldObserver>(
+        &mut self,
+        x: usize,
+        y: usize,
+        current_step: usize,
+        changes: &mut ChangeQueue<(usize, usize)>,
+        observer: &mut O,
+    ) -> bool {
+        let potential_index = match self.points[generate_index(x, y, self.width)].extract_selection()
+        {
+            Some(potential_index) => potential_index,
+            None => return true,
+        };
 
-        let current_step = self.steps.len();
+        for constraint in &self.spacing {
+            let excluded = constraint.excluded(potential_index);
+            let radius = constraint.min_distance().saturating_sub(1);
 
-        {
-            let mut points: &mut [FieldPoint] = &mut self.points;
+            if excluded.is_empty() || radius == 0 {
+                continue;
+            }
 
-            // Do not allow a potential to be on an edge if it requires a connection
-            // in that edge's direction.
-            for (potential_index, boundary) in self.boundaries.iter().enumerate() {
-                if boundary.requires(Direction::North) || boundary.requires(Direction::NorthEast)
-                    || boundary.requires(Direction::NorthWest)
-                {
-                    for x in 0..self.width {
-                        if !apply_failed_edge(
-                            x,
-                            0,
-                            self.width,
-                            potential_index,
-                            &mut points,
-                            current_step,
-                            &mut changes,
-                        ) {
-                            return false;
-                        }
+            for test_y in y.saturating_sub(radius)..(y + radius + 1).min(self.height) {
+                for test_x in x.saturating_sub(radius)..(x + radius + 1).min(self.width) {
+                    let test_index = generate_index(test_x, test_y, self.width);
+
+                    if (test_x, test_y) == (x, y) || !self.active[test_index]
+                        || constraint.metric().distance((x, y), (test_x, test_y)) > radius
+                    {
+                        continue;
                     }
-                }
 
-                if boundary.requires(Direction::East) || boundary.requires(Direction::NorthEast)
-                    || boundary.requires(Direction::SouthEast)
-                {
-                    for y in 0..self.height {
-                        let x = self.width - 1;
+                    let test_point = &mut self.points[test_index];
+                    let num_allowed = test_point.num_allowed;
 
-                        if !apply_failed_edge(
-                            x,
-                            y,
-                            self.width,
-                            potential_index,
-                            &mut points,
-                            current_step,
-                            &mut changes,
-                        ) {
-                            return false;
+                    for excluded_index in &excluded {
+                        if test_point.allowed[*excluded_index] {
+                            test_point.invalidate(*excluded_index, current_step);
+                            observer.on_invalidate(test_x, test_y, *excluded_index);
                         }
                     }
-                }
 
-                if boundary.requires(Direction::South) || boundary.requires(Direction::SouthEast)
-                    || boundary.requires(Direction::SouthWest)
-                {
-                    for x in 0..self.width {
-                        let y = self.height - 1;
-                        if !apply_failed_edge(
-                            x,
-                            y,
-                            self.width,
-                            potential_index,
-                            &mut points,
-                            current_step,
-                            &mut changes,
-                        ) {
-                            return false;
-                        }
+                    if test_point.num_allowed == 0 {
+                        observer.on_contradiction(Some((test_x, test_y)));
+                        return false;
                     }
-                }
 
-                if boundary.requires(Direction::West) || boundary.requires(Direction::NorthWest)
-                    || boundary.requires(Direction::SouthWest)
-                {
-                    for y in 0..self.height {
-                        if !apply_failed_edge(
-                            0,
-                            y,
-                            self.width,
-                            potential_index,
-                            &mut points,
-                            current_step,
-                            &mut changes,
-                        ) {
-                            return false;
-                        }
+                    if test_point.num_allowed != num_allowed {
+                        changes.add((test_x, test_y));
                     }
                 }
             }
         }
 
-        self.propagate(changes)
+        true
     }
 
-    pub fn force_potential(&mut self, x: usize, y: usize, potential_index: usize) -> bool {
-        let point_index = generate_index(x, y, self.width);
+    fn apply_count_constraints<O: FieldObserver>(
+        &mut self,
+        changes: &mut ChangeQueue<(usize, usize)>,
+        observer: &mut O,
+    ) -> bool {
+        let current_step = self.steps.len();
+        let num_cells = self.active.iter().filter(|a| **a).count();
 
-        {
-            let point = &mut self.points[point_index];
-            point.force(potential_index, self.steps.len());
-        }
+        for constraint in &self.count_constraints {
+            let potentials = constraint.potentials();
 
-        let mut changes = ChangeQueue::new();
-        changes.add((x, y));
+            let mut num_decided = 0;
+            let mut num_possible = 0;
 
-        self.propagate(changes)
-    }
+            for (point_index, point) in self.points.iter().enumerate() {
+                if self.active[point_index] && point.allows_any(potentials) {
+                    num_possible += 1;
 
-    pub fn step<R: Rng>(&mut self, mut rng: &mut R) -> bool {
-        let mut possible_best_point = self.observe(&mut rng);
+                    if point.num_allowed == 1 {
+                        num_decided += 1;
+                    }
+                }
+            }
 
-        loop {
-            match possible_best_point {
-                None => break false,
-                Some(FoundFieldPoint { point_index, .. }) => {
-                    match self.points[point_index].choose(&self.weights, &mut rng) {
-                        Some(choosen_potential) => {
-                            self.steps.push((point_index, choosen_potential));
+            let min = constraint.min_cells(num_cells);
+            let max = constraint.max_cells(num_cells);
 
-                            self.points[point_index].force(choosen_potential, self.steps.len());
+            if num_decided > max || num_possible < min {
+                observer.on_contradiction(None);
+                return false;
+            }
 
-                            let mut changes = ChangeQueue::new();
-                            changes.add(generate_coord(point_index, self.width));
+            // Once the limit is reached, nothing else may become one of the
+            // potentials.  Likewise, if only just enough cells remain to
+            // reach the minimum, they all must become one of them.
+            let ban_group = num_decided == max;
+            let force_group = num_possible == min;
+
+            if ban_group || force_group {
+                for (point_index, point) in self.points.iter_mut().enumerate() {
+                    if !self.active[point_index] || point.num_allowed == 1
+                        || !point.allows_any(potentials)
+                    {
+                        continue;
+                    }
 
-                            if self.propagate(changes) {
-                                break true;
-                            } else {
-                                possible_best_point = self.revert();
-                            }
-                        }
+                    let (x, y) = generate_coord(point_index, self.width);
+                    let num_allowed = point.num_allowed;
 
-                        None => {
-                            // This should never happen
-                            assert!(false);
-                            break false;
+                    for potential_index in 0..self.num_potentials {
+                        if constraint.contains(potential_index) == ban_group
+                            && point.allowed[potential_index]
+                        {
+                            point.invalidate(potential_index, current_step);
+                            observer.on_invalidate(x, y, potential_index);
                         }
                     }
+
+                    if point.num_allowed == 0 {
+                        observer.on_contradiction(Some((x, y)));
+                        return false;
+                    }
+
+                    if point.num_allowed != num_allowed {
+                        changes.add((x, y));
+                    }
                 }
             }
         }
+
+        true
     }
 
-    fn observe<R: Rng>(&self, mut rng: &mut R) -> Option<FoundFieldPoint> {
-        let mut result = None;
+    // Connectivity is only checked, never pruned: it fails once the cells
+    // can no longer possibly be connected as required.
+    fn check_connectivity(&self) -> bool {
+        for connectivity in &self.connectivity {
+            match *connectivity {
+                Connectivity::AllConnected => {
+                    let mut required = (0..self.points.len()).filter(|point_index| {
+                        self.active[*point_index] && self.requires_connection(*point_index)
+                    });
+
+                    if let Some(start) = required.next() {
+                        let reachable = self.find_reachable(start);
+
+                        if required.any(|point_index| !reachable[point_index]) {
+                            return false;
+                        }
+                    }
+                }
 
-        for (current_point_index, current_point) in self.points.iter().enumerate() {
-            if current_point.num_allowed > 1 {
-                match result {
-                    None => {
-                        result = Some(FoundFieldPoint::new(
-                            &current_point,
-                            current_point_index,
-                            &self.weights,
-                        ))
+                Connectivity::Connected((x0, y0), (x1, y1)) => {
+                    if !self.is_active(x0, y0) || !self.is_active(x1, y1) {
+                        return false;
                     }
-                    Some(best_point) => {
-                        let new_best_point = best_point.possibly_better(
-                            &current_point,
-                            current_point_index,
-                            &self.weights,
-                            &mut rng,
-                        );
-
-                        result = Some(new_best_point);
+
+                    let reachable = self.find_reachable(generate_index(x0, y0, self.width));
+
+                    if !reachable[generate_index(x1, y1, self.width)] {
+                        return false;
                     }
                 }
             }
         }
 
-        result
+        true
     }
 
-    fn propagate(&mut self, mut changes: ChangeQueue<(usize, usize)>) -> bool {
-        let current_step = self.steps.len();
+    // True if every remaining potential of the point connects somewhere.
+    fn requires_connection(&self, point_index: usize) -> bool {
+        let point = &self.points[point_index];
 
-        while !changes.is_empty() {
-            if let Some((x, y)) = changes.next() {
-                for direction in &Direction::ALL_DIRECTIONS {
-                    if !self.propagate_direction(x, y, current_step, *direction, &mut changes) {
-                        return false;
+        point.num_allowed > 0
+            && (0..self.num_potentials).all(|potential_index| {
+                !point.allowed[potential_index] || Direction::ALL_DIRECTIONS
+                    .iter()
+                    .any(|direction| self.requires(potential_index, *direction))
+            })
+    }
+
+    fn may_connect(&self, point_index: usize, direction: Direction) -> bool {
+        let point = &self.points[point_index];
+
+        (0..self.num_potentials).any(|potential_index| {
+            point.allowed[potential_index] && self.requires(potential_index, direction)
+        })
+    }
+
+    fn requires(&self, potential_index: usize, direction: Direction) -> bool {
+        self.parts[potential_index].requires(&self.boundaries[potential_index], direction)
+    }
+
+    // Flood fills from start along every connection that could still be made.
+    fn find_reachable(&self, start: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.points.len()];
+        let mut pending = vec![start];
+
+        reachable[start] = true;
+
+        while let Some(point_index) = pending.pop() {
+            let (x, y) = generate_coord(point_index, self.width);
+
+            for direction in &Direction::ALL_DIRECTIONS {
+                if let Some((test_x, test_y)) = self.build_delta(x, y, *direction) {
+                    let test_point_index = generate_index(test_x, test_y, self.width);
+
+                    if !reachable[test_point_index] && self.may_connect(point_index, *direction)
+                        && self.may_connect(test_point_index, direction.opposite())
+                    {
+                        reachable[test_point_index] = true;
+                        pending.push(test_point_index);
                     }
                 }
             }
         }
 
-        true
+        reachable
     }
 
-    fn revert(&mut self) -> Option<FoundFieldPoint> {
+    fn revert<O: FieldObserver>(&mut self, observer: &mut O) -> Option<usize> {
         if self.allow_backtracking {
             while let Some((point_index, chosen_potential)) = self.steps.pop() {
+                // Backtracking stops at a checkpoint, it is up to whoever
+                // made it to roll back past it.
+                if point_index == CHECKPOINT {
+                    self.steps.push((point_index, chosen_potential));
+                    break;
+                }
+
                 let current_step = self.steps.len();
+                let (x, y) = generate_coord(point_index, self.width);
+
+                observer.on_backtrack(x, y, chosen_potential);
 
                 // Restore points to their values at the step we just reverted.
                 for point in &mut self.points {
@@ -464,16 +1573,12 @@ impl Field {
 
                 // Propagate that invalidation.
                 let mut revert_changes = ChangeQueue::new();
-                revert_changes.add(generate_coord(point_index, self.width));
+                revert_changes.add((x, y));
 
-                if self.propagate(revert_changes) {
+                if self.propagate_observed(revert_changes, observer) {
                     // We are back to consistent state, loop around knowing
                     // that we won't choose that option again.
-                    return Some(FoundFieldPoint::new(
-                        &self.points[point_index],
-                        point_index,
-                        &self.weights,
-                    ));
+                    return Some(point_index);
                 }
 
                 // Invalidating that choice left us in an inconsistent state still,
@@ -485,6 +1590,35 @@ impl Field {
         }
         None
     }
+
+    // Starts a new step that is not a choice, so that everything removed
+    // until the next one can be undone together by rollback.
+    pub(crate) fn checkpoint(&mut self) {
+        self.steps.push((CHECKPOINT, CHECKPOINT));
+    }
+
+    // Undoes every step and restriction since the last checkpoint, along with
+    // the checkpoint itself.  Returns false if there is no checkpoint.
+    pub(crate) fn rollback(&mut self) -> bool {
+        let checkpoint = self
+            .steps
+            .iter()
+            .rposition(|&(point_index, _)| point_index == CHECKPOINT);
+
+        match checkpoint {
+            Some(step) => {
+                self.steps.truncate(step);
+
+                for point in &mut self.points {
+                    point.revert_to(step);
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn render(&self) -> Option<Vec<Vec<usize>>> {
         let mut result = Vec::with_capacity(self.height);
 
@@ -495,7 +1629,9 @@ impl Field {
                 let point_index = generate_index(x, y, self.width);
                 let point = &self.points[point_index];
 
-                if let Some(i) = point.extract_selection() {
+                if !self.active[point_index] {
+                    row.push(MASKED);
+                } else if let Some(i) = point.extract_selection() {
                     row.push(i);
                 } else {
                     return None;
@@ -518,12 +1654,14 @@ impl Field {
                 let point_index = generate_index(x, y, self.width);
                 let point = &self.points[point_index];
 
-                if let Some(i) = point.extract_selection() {
+                if !self.active[point_index] {
+                    row.push(MASKED);
+                } else if let Some(i) = point.extract_selection() {
                     row.push(i);
                 } else if point.num_allowed > 0 {
                     row.push(self.num_potentials);
                 } else {
-                    row.push(usize::max_value());
+                    row.push(usize::MAX);
                 }
             }
 
@@ -533,29 +1671,33 @@ impl Field {
         result
     }
 
-    fn propagate_direction(
+    fn propagate_direction<O: FieldObserver>(
         &mut self,
         x: usize,
         y: usize,
         current_step: usize,
         direction: Direction,
         changes: &mut ChangeQueue<(usize, usize)>,
+        observer: &mut O,
     ) -> bool {
         if let Some((test_x, test_y)) = self.build_delta(x, y, direction) {
             let source_point_index = generate_index(x, y, self.width);
             let test_point_index = generate_index(test_x, test_y, self.width);
 
-            if let Some((source_point, mut test_point)) =
+            if let Some((source_point, test_point)) =
                 extract_two_elements(&mut self.points, source_point_index, test_point_index)
             {
                 if test_direction(
+                    &self.parts,
                     &self.boundaries,
                     source_point,
-                    &mut test_point,
+                    test_point,
                     current_step,
                     direction,
+                    |test_index| observer.on_invalidate(test_x, test_y, test_index),
                 ) {
                     if test_point.num_allowed == 0 {
+                        observer.on_contradiction(Some((test_x, test_y)));
                         return false;
                     }
 
@@ -567,11 +1709,18 @@ impl Field {
         true
     }
 
+    // Returns the neighboring cell that propagation should visit, skipping any
+    // neighbors that are masked out.
     fn build_delta(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        self.neighbor(x, y, direction)
+            .filter(|&(test_x, test_y)| self.is_active(test_x, test_y))
+    }
+
+    fn neighbor(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
         match direction {
             Direction::NorthWest if x > 0 && y > 0 => Some((x - 1, y - 1)),
             Direction::North if y > 0 => Some((x, y - 1)),
-            Direction::NorthEast if x < self.height - 1 && y > 0 => Some((x + 1, y - 1)),
+            Direction::NorthEast if x < self.width - 1 && y > 0 => Some((x + 1, y - 1)),
             Direction::East if x < self.width - 1 => Some((x + 1, y)),
             Direction::SouthEast if x < self.width - 1 && y < self.height - 1 => {
                 Some((x + 1, y + 1))
@@ -593,12 +1742,18 @@ fn generate_coord(point_index: usize, width: usize) -> (usize, usize) {
     (point_index % width, point_index / width)
 }
 
-fn test_direction(
+fn has_shape<T>(grid: &[Vec<T>], width: usize, height: usize) -> bool {
+    grid.len() == height && grid.iter().all(|row| row.len() == width)
+}
+
+fn test_direction<F: FnMut(usize)>(
+    parts: &[Part],
     potentials: &[Boundary],
     source_point: &FieldPoint,
     test_point: &mut FieldPoint,
     current_step: usize,
     direction: Direction,
+    mut on_invalidate: F,
 ) -> bool {
     let mut changed = false;
 
@@ -607,16 +1762,23 @@ fn test_direction(
             let mut fits = false;
 
             for source_index in 0..potentials.len() {
-                if source_point.allowed[source_index] {
-                    if potentials[source_index].fits(&potentials[test_index], direction) {
-                        fits = true;
-                        break;
-                    }
+                if source_point.allowed[source_index]
+                    && parts_fit(
+                        &parts[source_index],
+                        &potentials[source_index],
+                        &parts[test_index],
+                        &potentials[test_index],
+                        direction,
+                    )
+                {
+                    fits = true;
+                    break;
                 }
             }
 
             if !fits {
                 test_point.invalidate(test_index, current_step);
+                on_invalidate(test_index);
                 changed = true;
             }
         }
@@ -625,7 +1787,7 @@ fn test_direction(
     changed
 }
 
-fn apply_failed_edge(
+fn invalidate_and_queue(
     x: usize,
     y: usize,
     width: usize,
@@ -651,9 +1813,10 @@ mod tests {
 
     use super::*;
 
-    use entry::CharacterEntry;
     use rand;
 
+    use entry::CharacterEntry;
+
     #[test]
     fn initialize_fieldpoint() {
         let f0 = FieldPoint::new(3);
@@ -680,20 +1843,20 @@ mod tests {
         assert_eq!(f0.num_allowed, 3);
 
         f0.invalidate(0, 0);
-        assert_eq!(f0.allowed[0], false);
+        assert!(!f0.allowed[0]);
         assert_eq!(f0.num_allowed, 2);
 
         f0.invalidate(2, 0);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
 
         // test repeated invalidation
         f0.invalidate(2, 0);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
 
         f0.invalidate(1, 0);
-        assert_eq!(f0.allowed[1], false);
+        assert!(!f0.allowed[1]);
         assert_eq!(f0.num_allowed, 0);
     }
 
@@ -701,117 +1864,107 @@ mod tests {
     fn revert_fieldpoint() {
         let mut f0 = FieldPoint::new(3);
 
-        assert_eq!(f0.allowed[0], true);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], true);
+        assert!(f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(f0.allowed[2]);
         assert_eq!(f0.num_allowed, 3);
         assert_eq!(f0.max_invalidate_step, None);
 
         f0.invalidate(0, 1);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], true);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(f0.allowed[2]);
         assert_eq!(f0.num_allowed, 2);
         assert_eq!(f0.max_invalidate_step, Some(1));
 
         f0.invalidate(2, 2);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
         assert_eq!(f0.max_invalidate_step, Some(2));
 
         // test repeated invalidation
         f0.invalidate(2, 3);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
         assert_eq!(f0.max_invalidate_step, Some(2));
 
         f0.invalidate(1, 4);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], false);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[0]);
+        assert!(!f0.allowed[1]);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 0);
         assert_eq!(f0.max_invalidate_step, Some(4));
 
         f0.revert_to(3);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
         assert_eq!(f0.max_invalidate_step, Some(2));
 
         f0.revert_to(2);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], false);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(!f0.allowed[2]);
         assert_eq!(f0.num_allowed, 1);
         assert_eq!(f0.max_invalidate_step, Some(2));
 
         f0.revert_to(1);
-        assert_eq!(f0.allowed[0], false);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], true);
+        assert!(!f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(f0.allowed[2]);
         assert_eq!(f0.num_allowed, 2);
         assert_eq!(f0.max_invalidate_step, Some(1));
 
         f0.revert_to(0);
-        assert_eq!(f0.allowed[0], true);
-        assert_eq!(f0.allowed[1], true);
-        assert_eq!(f0.allowed[2], true);
+        assert!(f0.allowed[0]);
+        assert!(f0.allowed[1]);
+        assert!(f0.allowed[2]);
         assert_eq!(f0.num_allowed, 3);
         assert_eq!(f0.max_invalidate_step, None);
     }
 
     #[test]
     fn field_point_choose() {
-        let weights = [
-            PointWeight::new(0.1),
-            PointWeight::new(0.2),
-            PointWeight::new(0.3),
-            PointWeight::new(10.1),
+        let potentials = [
+            CharacterEntry::build('a', 0.1, "000|000|000").unwrap(),
+            CharacterEntry::build('b', 0.2, "000|000|000").unwrap(),
+            CharacterEntry::build('c', 0.3, "000|000|000").unwrap(),
+            CharacterEntry::build('d', 10.1, "000|000|000").unwrap(),
         ];
 
         let mut rng = rand::thread_rng();
 
-        let fieldpoint = FieldPoint::new(4);
+        let field = Field::new(&potentials, 1, 1);
 
         for _ in 0..20 {
-            let chosen_index = fieldpoint.choose(&weights, &mut rng).unwrap();
+            let chosen_index = WeightedRandom.choose(&field, 0, 0, &mut rng).unwrap();
             assert!(chosen_index < 4);
         }
     }
 
     #[test]
     fn found_fieldpoint() {
-        let weights = [
-            PointWeight::new(0.1),
-            PointWeight::new(0.2),
-            PointWeight::new(0.3),
-            PointWeight::new(10.1),
+        let potentials = [
+            CharacterEntry::build('a', 0.1, "000|000|000").unwrap(),
+            CharacterEntry::build('b', 0.2, "000|000|000").unwrap(),
+            CharacterEntry::build('c', 0.3, "000|000|000").unwrap(),
+            CharacterEntry::build('d', 10.1, "000|000|000").unwrap(),
         ];
 
         let mut rng = rand::thread_rng();
 
-        let mut fieldpoint_0 = FieldPoint::new(4);
-        let mut fieldpoint_1 = FieldPoint::new(4);
+        let mut field = Field::new(&potentials, 2, 1);
 
-        fieldpoint_1.invalidate(0, 0);
+        field.points[1].invalidate(0, 0);
+        assert_eq!(MinEntropy.select(&field, &mut rng), Some((1, 0)));
 
-        let inital_best_fieldpoint_a = FoundFieldPoint::new(&fieldpoint_0, 0, &weights);
-        let best_fieldpoint_a =
-            inital_best_fieldpoint_a.possibly_better(&fieldpoint_1, 1, &weights, &mut rng);
-
-        assert_eq!(best_fieldpoint_a.point_index, 1);
-
-        fieldpoint_0.invalidate(1, 0);
-        let inital_best_fieldpoint_b = FoundFieldPoint::new(&fieldpoint_0, 0, &weights);
-        let best_fieldpoint_b =
-            inital_best_fieldpoint_b.possibly_better(&fieldpoint_1, 1, &weights, &mut rng);
-
-        assert_eq!(best_fieldpoint_b.point_index, 0);
+        field.points[0].invalidate(1, 0);
+        assert_eq!(MinEntropy.select(&field, &mut rng), Some((0, 0)));
     }
 
     #[test]
@@ -826,13 +1979,13 @@ mod tests {
 
         let test_point_index = generate_index(0, 0, field.width);
 
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], true);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(field.points[test_point_index].allowed[2]);
         assert!(field.force_potential(0, 1, 1));
-        assert_eq!(field.points[test_point_index].allowed[0], false);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], false);
+        assert!(!field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(!field.points[test_point_index].allowed[2]);
     }
 
     #[test]
@@ -847,13 +2000,13 @@ mod tests {
 
         let test_point_index = generate_index(0, 1, field.width);
 
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], true);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(field.points[test_point_index].allowed[2]);
         assert!(field.force_potential(0, 0, 1));
-        assert_eq!(field.points[test_point_index].allowed[0], false);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], false);
+        assert!(!field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(!field.points[test_point_index].allowed[2]);
     }
 
     #[test]
@@ -868,13 +2021,13 @@ mod tests {
 
         let test_point_index = generate_index(1, 0, field.width);
 
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], true);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(field.points[test_point_index].allowed[2]);
         assert!(field.force_potential(0, 0, 0));
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], false);
-        assert_eq!(field.points[test_point_index].allowed[2], false);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(!field.points[test_point_index].allowed[1]);
+        assert!(!field.points[test_point_index].allowed[2]);
     }
 
     #[test]
@@ -889,13 +2042,13 @@ mod tests {
 
         let test_point_index = generate_index(0, 0, field.width);
 
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], true);
-        assert_eq!(field.points[test_point_index].allowed[2], true);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(field.points[test_point_index].allowed[1]);
+        assert!(field.points[test_point_index].allowed[2]);
         assert!(field.force_potential(1, 0, 0));
-        assert_eq!(field.points[test_point_index].allowed[0], true);
-        assert_eq!(field.points[test_point_index].allowed[1], false);
-        assert_eq!(field.points[test_point_index].allowed[2], false);
+        assert!(field.points[test_point_index].allowed[0]);
+        assert!(!field.points[test_point_index].allowed[1]);
+        assert!(!field.points[test_point_index].allowed[2]);
     }
 
     #[test]
@@ -910,10 +2063,34 @@ mod tests {
         let field = Field::new(&potentials, 2, 2);
 
         for p in field.points {
-            assert_eq!(p.allowed[0], true);
-            assert_eq!(p.allowed[1], true);
-            assert_eq!(p.allowed[2], false);
-            assert_eq!(p.allowed[3], false);
+            assert!(p.allowed[0]);
+            assert!(p.allowed[1]);
+            assert!(!p.allowed[2]);
+            assert!(!p.allowed[3]);
         }
     }
+
+    #[test]
+    fn rollback_to_checkpoint() {
+        let potentials = [
+            CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+            CharacterEntry::build('|', 1.0, "010|000|010").unwrap(),
+            CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        ];
+
+        let mut field = Field::new(&potentials, 2, 2);
+
+        assert!(!field.rollback());
+
+        assert!(field.force_potential(0, 0, 1));
+        field.checkpoint();
+        assert!(field.force_potential(1, 0, 1));
+        assert!(field.ban(1, 1, 2));
+
+        assert!(field.rollback());
+        assert!(field.steps.is_empty());
+        assert_eq!(field.allowed_potentials(0, 0), vec![1]);
+        assert_eq!(field.allowed_potentials(1, 0), vec![1, 2]);
+        assert_eq!(field.allowed_potentials(1, 1), vec![1, 2]);
+    }
 }
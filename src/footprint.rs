@@ -0,0 +1,112 @@
+use boundary::{Boundary, Direction};
+
+// One cell of an entry's footprint.  Every cell of a multi-cell entry becomes
+// its own potential in the field, and single cell entries have exactly one
+// part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Part {
+    pub entry: usize,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Part {
+    // Splits every entry's footprint into parts, in entry order and then row
+    // major order within each footprint.
+    pub fn split(footprints: &[(usize, usize)]) -> Vec<Part> {
+        let mut parts = Vec::new();
+
+        for (entry, &(width, height)) in footprints.iter().enumerate() {
+            for y in 0..height {
+                for x in 0..width {
+                    parts.push(Part {
+                        entry,
+                        x,
+                        y,
+                        width,
+                        height,
+                    });
+                }
+            }
+        }
+
+        parts
+    }
+
+    // The position of the part in direction, if it is inside the footprint.
+    pub fn inner_neighbor(&self, direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.offset();
+        let x = self.x as isize + dx;
+        let y = self.y as isize + dy;
+
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    // True if the side of the part facing direction is on the outside of the
+    // footprint, where the entry's Boundary applies.
+    pub fn is_outer(&self, direction: Direction) -> bool {
+        self.inner_neighbor(direction).is_none()
+    }
+
+    // True if this part needs something to be placed in direction, either
+    // the rest of its footprint or a connection required by its boundary.
+    pub fn requires(&self, boundary: &Boundary, direction: Direction) -> bool {
+        !self.is_outer(direction) || boundary.requires(direction)
+    }
+}
+
+// Returns true if test may be placed in direction from source.  Inside a
+// footprint only the matching part of the same entry fits, while the outer
+// edges are matched by the entries' boundaries.
+pub fn parts_fit(
+    source: &Part,
+    source_boundary: &Boundary,
+    test: &Part,
+    test_boundary: &Boundary,
+    direction: Direction,
+) -> bool {
+    match source.inner_neighbor(direction) {
+        Some((x, y)) => test.entry == source.entry && test.x == x && test.y == y,
+        None => {
+            test.is_outer(direction.opposite()) && source_boundary.fits(test_boundary, direction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn split_footprints() {
+        let parts = Part::split(&[(1, 1), (2, 2), (3, 1)]);
+
+        assert_eq!(parts.len(), 8);
+        assert_eq!(parts[0].entry, 0);
+        assert_eq!((parts[3].entry, parts[3].x, parts[3].y), (1, 0, 1));
+        assert_eq!((parts[7].entry, parts[7].x, parts[7].y), (2, 2, 0));
+    }
+
+    #[test]
+    fn inner_parts_fit() {
+        let parts = Part::split(&[(2, 2), (1, 1)]);
+        let open = Boundary::from_str("000|000|000").unwrap();
+
+        assert!(parts_fit(&parts[0], &open, &parts[1], &open, Direction::East));
+        assert!(parts_fit(&parts[0], &open, &parts[3], &open, Direction::SouthEast));
+        assert!(!parts_fit(&parts[0], &open, &parts[4], &open, Direction::East));
+        assert!(parts_fit(&parts[1], &open, &parts[0], &open, Direction::East));
+        assert!(!parts_fit(&parts[1], &open, &parts[1], &open, Direction::West));
+
+        assert!(parts_fit(&parts[1], &open, &parts[4], &open, Direction::East));
+        assert!(!parts_fit(&parts[4], &open, &parts[1], &open, Direction::East));
+        assert!(parts_fit(&parts[4], &open, &parts[0], &open, Direction::East));
+    }
+}
@@ -0,0 +1,145 @@
+use rand::Rng;
+
+use field::Field;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generated {
+    pub coarse: Vec<Vec<usize>>,
+    pub fine: Vec<Vec<usize>>,
+}
+
+// Generates in two levels.  A coarse field of region types is solved first,
+// then every coarse cell becomes a block of cells in the fine field, limited
+// to the fine potentials allowed for its region.  The fine field is solved as
+// a whole, so its propagation crosses the borders between blocks.
+#[derive(Debug, Clone)]
+pub struct Hierarchy {
+    coarse: Field,
+    fine: Field,
+    regions: Vec<(usize, Vec<usize>)>, // (coarse potential, allowed fine potentials)
+    max_attempts: usize,
+}
+
+impl Hierarchy {
+    // The fine field must be a whole number of blocks in each direction, one
+    // for every coarse cell.
+    pub fn new(coarse: Field, fine: Field) -> Result<Hierarchy, String> {
+        if coarse.width() == 0 || coarse.height() == 0
+            || !fine.width().is_multiple_of(coarse.width())
+            || !fine.height().is_multiple_of(coarse.height())
+        {
+            return Err(format!(
+                "Hierarchy::new() is incorrect.  A {}x{} fine field can not be split into {}x{} blocks.",
+                fine.width(),
+                fine.height(),
+                coarse.width(),
+                coarse.height()
+            ));
+        }
+
+        Ok(Hierarchy {
+            coarse,
+            fine,
+            regions: Vec::new(),
+            max_attempts: 20,
+        })
+    }
+
+    // The blocks of coarse_potential may only hold fine_potentials.  Coarse
+    // potentials without a region leave their blocks unrestricted, and
+    // calling this again for the same coarse potential adds to its region.
+    pub fn with_region(mut self, coarse_potential: usize, fine_potentials: &[usize]) -> Hierarchy {
+        match self.regions.iter().position(|&(p, _)| p == coarse_potential) {
+            Some(i) => self.regions[i].1.extend_from_slice(fine_potentials),
+            None => self.regions.push((coarse_potential, fine_potentials.to_vec())),
+        }
+
+        self
+    }
+
+    pub fn with_max_attempts(self, max_attempts: usize) -> Hierarchy {
+        Hierarchy {
+            max_attempts,
+            ..self
+        }
+    }
+
+    pub fn block_width(&self) -> usize {
+        self.fine.width() / self.coarse.width()
+    }
+
+    pub fn block_height(&self) -> usize {
+        self.fine.height() / self.coarse.height()
+    }
+
+    // Solves both levels, starting over with a new coarse layout whenever the
+    // fine field can not be solved for it.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<Generated, String> {
+        for _ in 0..self.max_attempts {
+            if let Some(coarse) = self.coarse.clone().solve(rng) {
+                if let Ok(fine) = self.refine(&coarse, rng) {
+                    return Ok(Generated { coarse, fine });
+                }
+            }
+        }
+
+        Err(format!(
+            "Hierarchy failed to generate after {} attempts.",
+            self.max_attempts
+        ))
+    }
+
+    // Solves the fine field for an already solved coarse layout.
+    pub fn refine<R: Rng>(
+        &self,
+        coarse: &[Vec<usize>],
+        rng: &mut R,
+    ) -> Result<Vec<Vec<usize>>, String> {
+        let mut field = self.fine.clone();
+        let block_width = self.block_width();
+        let block_height = self.block_height();
+
+        if coarse.len() != self.coarse.height()
+            || coarse.iter().any(|row| row.len() != self.coarse.width())
+        {
+            return Err(format!(
+                "Hierarchy::refine() is incorrect.  Expected a coarse layout of {}x{} cells.",
+                self.coarse.width(),
+                self.coarse.height()
+            ));
+        }
+
+        for (coarse_y, row) in coarse.iter().enumerate() {
+            for (coarse_x, coarse_potential) in row.iter().enumerate() {
+                let allowed = match self.regions.iter().find(|&&(p, _)| p == *coarse_potential) {
+                    Some((_, allowed)) => allowed,
+                    None => continue,
+                };
+
+                if !field.restrict_region(
+                    coarse_x * block_width,
+                    coarse_y * block_height,
+                    block_width,
+                    block_height,
+                    allowed,
+                ) {
+                    return Err(format!(
+                        "The block for coarse cell ({}, {}) can not be filled.",
+                        coarse_x, coarse_y
+                    ));
+                }
+            }
+        }
+
+        for _ in 0..self.max_attempts {
+            if let Some(fine) = field.clone().solve(rng) {
+                return Ok(fine);
+            }
+        }
+
+        Err(format!(
+            "Hierarchy failed to refine the coarse layout after {} attempts.",
+            self.max_attempts
+        ))
+    }
+}
@@ -0,0 +1,247 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+use rand;
+
+use wfc::entry::CharacterEntry;
+use wfc::field::{Field, MASKED};
+
+const HELP: &str = "s: step  r: run  u: undo  arrows/hjkl: move  \
+                    n/p: next/previous tile  f: force tile  q: quit";
+
+// Puts the terminal into single key mode for as long as it is alive, and
+// restores the previous settings when dropped.  If stty is not available
+// the terminal is left alone, and each key needs to be followed by enter.
+struct RawTerminal {
+    saved_settings: Option<String>,
+}
+
+impl RawTerminal {
+    fn new() -> RawTerminal {
+        let saved_settings = stty(&["-g"]).map(|s| s.trim().to_string());
+
+        if saved_settings.is_some() {
+            stty(&["-icanon", "-echo", "min", "1"]);
+        }
+
+        RawTerminal { saved_settings }
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        if let Some(ref settings) = self.saved_settings {
+            stty(&[settings]);
+        }
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+            } else {
+                None
+            }
+        })
+}
+
+enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+fn read_key<R: Read>(input: &mut io::Bytes<R>) -> Option<Key> {
+    match input.next()?.ok()? {
+        // Arrow keys arrive as ESC [ A through ESC [ D.
+        0x1b => {
+            if input.next()?.ok()? != b'[' {
+                return Some(Key::Char('\x1b'));
+            }
+
+            match input.next()?.ok()? {
+                b'A' => Some(Key::Up),
+                b'B' => Some(Key::Down),
+                b'C' => Some(Key::Right),
+                b'D' => Some(Key::Left),
+                other => Some(Key::Char(other as char)),
+            }
+        }
+        byte => Some(Key::Char(byte as char)),
+    }
+}
+
+struct Stepper<'a> {
+    potentials: &'a [CharacterEntry],
+    field: Field,
+    history: Vec<Field>,
+    cursor: (usize, usize),
+    selected: usize,
+    message: String,
+}
+
+impl<'a> Stepper<'a> {
+    fn step<R: rand::Rng>(&mut self, rng: &mut R) {
+        if self.field.render().is_some() {
+            self.message = String::from("The field is already solved.");
+            return;
+        }
+
+        self.history.push(self.field.clone());
+
+        self.message = if self.field.step(rng) {
+            String::from("Stepped.")
+        } else {
+            String::from("Step failed, undo to try again.")
+        };
+    }
+
+    fn run<R: rand::Rng>(&mut self, rng: &mut R) {
+        self.history.push(self.field.clone());
+
+        self.message = if self.field.solve(rng).is_some() {
+            String::from("Solved.")
+        } else {
+            String::from("Run failed, undo to try again.")
+        };
+    }
+
+    fn undo(&mut self) {
+        if let Some(field) = self.history.pop() {
+            self.field = field;
+            self.message = String::from("Undone.");
+        } else {
+            self.message = String::from("Nothing to undo.");
+        }
+    }
+
+    fn force(&mut self) {
+        let (x, y) = self.cursor;
+
+        self.history.push(self.field.clone());
+
+        self.message = if self.field.force_potential(x, y, self.selected) {
+            format!(
+                "Forced '{}' at ({}, {}).",
+                self.potentials[self.selected].character, x, y
+            )
+        } else {
+            format!(
+                "Forcing '{}' at ({}, {}) failed, undo to try again.",
+                self.potentials[self.selected].character, x, y
+            )
+        };
+    }
+
+    fn move_cursor(&mut self, delta_x: isize, delta_y: isize) {
+        let (x, y) = self.cursor;
+        let max_x = self.field.width() as isize - 1;
+        let max_y = self.field.height() as isize - 1;
+
+        self.cursor = (
+            (x as isize + delta_x).max(0).min(max_x) as usize,
+            (y as isize + delta_y).max(0).min(max_y) as usize,
+        );
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        // Clear the screen and move to the top left.
+        write!(out, "\x1b[2J\x1b[H")?;
+
+        for (y, row) in self.field.render_partial().iter().enumerate() {
+            for (x, index) in row.iter().enumerate() {
+                let character = if *index < self.potentials.len() {
+                    self.potentials[*index].character
+                } else if *index == self.potentials.len() {
+                    '?'
+                } else if *index == MASKED {
+                    ' '
+                } else {
+                    '!'
+                };
+
+                let is_cursor = (x, y) == self.cursor;
+                let is_contradiction = *index > self.potentials.len() && *index != MASKED;
+
+                // Inverse video for the cursor, red for contradictions.
+                match (is_cursor, is_contradiction) {
+                    (true, true) => write!(out, "\x1b[7;31m{}\x1b[0m", character)?,
+                    (true, false) => write!(out, "\x1b[7m{}\x1b[0m", character)?,
+                    (false, true) => write!(out, "\x1b[41m{}\x1b[0m", character)?,
+                    (false, false) => write!(out, "{}", character)?,
+                }
+            }
+
+            write!(out, "\r\n")?;
+        }
+
+        write!(
+            out,
+            "\r\ncursor: ({}, {})  tile: '{}' ({})\r\n{}\r\n{}\r\n",
+            self.cursor.0,
+            self.cursor.1,
+            self.potentials[self.selected].character,
+            self.selected,
+            self.message,
+            HELP
+        )?;
+
+        out.flush()
+    }
+}
+
+pub fn run(potentials: &[CharacterEntry], field: Field) -> Result<(), String> {
+    if potentials.is_empty() || field.width() == 0 || field.height() == 0 {
+        return Err(String::from("Nothing to step through."));
+    }
+
+    let _terminal = RawTerminal::new();
+
+    let mut rng = rand::thread_rng();
+    let stdin = io::stdin();
+    let mut input = stdin.lock().bytes();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    let mut stepper = Stepper {
+        potentials,
+        field,
+        history: Vec::new(),
+        cursor: (0, 0),
+        selected: 0,
+        message: String::new(),
+    };
+
+    loop {
+        stepper.draw(&mut out).map_err(|e| e.to_string())?;
+
+        match read_key(&mut input) {
+            None | Some(Key::Char('q')) => break,
+            Some(Key::Char('s')) => stepper.step(&mut rng),
+            Some(Key::Char('r')) => stepper.run(&mut rng),
+            Some(Key::Char('u')) => stepper.undo(),
+            Some(Key::Char('f')) => stepper.force(),
+            Some(Key::Char('n')) => {
+                stepper.selected = (stepper.selected + 1) % potentials.len();
+            }
+            Some(Key::Char('p')) => {
+                stepper.selected = (stepper.selected + potentials.len() - 1) % potentials.len();
+            }
+            Some(Key::Up) | Some(Key::Char('k')) => stepper.move_cursor(0, -1),
+            Some(Key::Down) | Some(Key::Char('j')) => stepper.move_cursor(0, 1),
+            Some(Key::Left) | Some(Key::Char('h')) => stepper.move_cursor(-1, 0),
+            Some(Key::Right) | Some(Key::Char('l')) => stepper.move_cursor(1, 0),
+            Some(Key::Char(_)) => {}
+        }
+    }
+
+    Ok(())
+}
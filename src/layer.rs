@@ -0,0 +1,397 @@
+use rand::Rng;
+
+use changequeue::ChangeQueue;
+use field::Field;
+use observer::FieldObserver;
+
+// Limits which potentials of two layers may share the same cell.
+#[derive(Debug, Clone)]
+pub struct LayerRule {
+    layer_a: usize,
+    layer_b: usize,
+    allowed: Vec<(usize, Vec<usize>)>, // (potential_a, allowed potentials of b)
+}
+
+impl LayerRule {
+    pub fn new(layer_a: usize, layer_b: usize) -> LayerRule {
+        LayerRule {
+            layer_a,
+            layer_b,
+            allowed: Vec::new(),
+        }
+    }
+
+    // potential_a may only share a cell with the potentials of layer_b in
+    // allowed_b.  Potentials of layer_a that are never passed to allow may
+    // share a cell with anything.
+    pub fn allow(mut self, potential_a: usize, allowed_b: &[usize]) -> LayerRule {
+        match self.allowed.iter().position(|&(p, _)| p == potential_a) {
+            Some(i) => self.allowed[i].1.extend_from_slice(allowed_b),
+            None => self.allowed.push((potential_a, allowed_b.to_vec())),
+        }
+
+        self
+    }
+
+    pub fn layers(&self) -> (usize, usize) {
+        (self.layer_a, self.layer_b)
+    }
+
+    fn allowed_b(&self, potential_a: usize) -> Option<&[usize]> {
+        self.allowed
+            .iter()
+            .find(|&&(p, _)| p == potential_a)
+            .map(|(_, allowed_b)| allowed_b.as_slice())
+    }
+
+    // The potentials of layer_b that can share the cell with what is left in
+    // layer_a.
+    fn supported_b(&self, field_a: &Field, field_b: &Field, x: usize, y: usize) -> Vec<usize> {
+        (0..field_b.num_potentials())
+            .filter(|potential_b| {
+                field_a.allowed_potentials(x, y).into_iter().any(|potential_a| {
+                    self.allowed_b(potential_a)
+                        .is_none_or(|allowed_b| allowed_b.contains(potential_b))
+                })
+            })
+            .collect()
+    }
+
+    // The potentials of layer_a that can share the cell with what is left in
+    // layer_b.
+    fn supported_a(&self, field_a: &Field, field_b: &Field, x: usize, y: usize) -> Vec<usize> {
+        (0..field_a.num_potentials())
+            .filter(|potential_a| match self.allowed_b(*potential_a) {
+                Some(allowed_b) => allowed_b.iter().any(|p| field_b.is_allowed(x, y, *p)),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+// Several fields of the same size stacked on top of each other, such as
+// terrain and the objects placed on it.  Each layer keeps its own entries and
+// adjacency, while LayerRules tie together the cells the layers share.
+#[derive(Debug, Clone)]
+pub struct LayeredField {
+    layers: Vec<Field>,
+    rules: Vec<LayerRule>,
+
+    allow_backtracking: bool,
+    history: Vec<(usize, usize, usize, usize)>, // (layer, x, y, potential)
+}
+
+impl LayeredField {
+    pub fn new(layers: Vec<Field>) -> Result<LayeredField, String> {
+        if layers.is_empty() {
+            return Err(String::from("LayeredField::new() needs at least one layer."));
+        }
+
+        let (width, height) = (layers[0].width(), layers[0].height());
+
+        if layers
+            .iter()
+            .any(|layer| layer.width() != width || layer.height() != height)
+        {
+            return Err(format!(
+                "LayeredField::new() is incorrect.  Every layer must be {}x{} cells.",
+                width, height
+            ));
+        }
+
+        Ok(LayeredField {
+            layers,
+            rules: Vec::new(),
+            allow_backtracking: false,
+            history: Vec::new(),
+        })
+    }
+
+    // Lets step undo earlier steps, in any layer, when a choice turns out to
+    // contradict the other layers.  Every step starts with a checkpoint in
+    // each layer, so undoing it only needs what the layers already track.
+    pub fn allow_backtracking(self) -> LayeredField {
+        LayeredField {
+            allow_backtracking: true,
+            ..self
+        }
+    }
+
+    // Adds the rule and applies it to every cell.  Fails if the rule names a
+    // layer or potential that does not exist, or leaves no solution.
+    pub fn with_rule(mut self, rule: LayerRule) -> Result<LayeredField, String> {
+        let (layer_a, layer_b) = rule.layers();
+
+        if layer_a >= self.layers.len() || layer_b >= self.layers.len() || layer_a == layer_b {
+            return Err(format!(
+                "LayerRule between layers {} and {} is incorrect.  There are {} layers.",
+                layer_a,
+                layer_b,
+                self.layers.len()
+            ));
+        }
+
+        let num_potentials_a = self.layers[layer_a].num_potentials();
+        let num_potentials_b = self.layers[layer_b].num_potentials();
+
+        for &(potential_a, ref allowed_b) in &rule.allowed {
+            if potential_a >= num_potentials_a || allowed_b.iter().any(|p| *p >= num_potentials_b)
+            {
+                return Err(format!(
+                    "LayerRule between layers {} and {} names a potential that does not exist.",
+                    layer_a, layer_b
+                ));
+            }
+        }
+
+        self.rules.push(rule);
+
+        let mut changes = ChangeQueue::new();
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                changes.add((layer_a, x, y));
+                changes.add((layer_b, x, y));
+            }
+        }
+
+        if self.settle(changes) {
+            Ok(self)
+        } else {
+            Err(format!(
+                "LayerRule between layers {} and {} leaves no solution.",
+                layer_a, layer_b
+            ))
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.layers[0].width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.layers[0].height()
+    }
+
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn layer(&self, layer: usize) -> &Field {
+        &self.layers[layer]
+    }
+
+    pub fn restrict(&mut self, layer: usize, x: usize, y: usize, allowed: &[usize]) -> bool {
+        let mut changes = ChangeQueue::new();
+
+        self.restrict_layer(layer, x, y, allowed, &mut changes) && self.settle(changes)
+    }
+
+    pub fn force_potential(
+        &mut self,
+        layer: usize,
+        x: usize,
+        y: usize,
+        potential_index: usize,
+    ) -> bool {
+        self.layers[layer].is_allowed(x, y, potential_index)
+            && self.restrict(layer, x, y, &[potential_index])
+    }
+
+    // Collapses a cell of the first layer that is not yet solved, so layers
+    // are generated in order.  When the choice leads to a contradiction, in
+    // its own layer or another, the step fails unless backtracking is
+    // allowed.  Then the choice is banned, and earlier steps are undone for
+    // as long as that still leaves a contradiction.  The layer's own
+    // backtracking is discarded, so that undoing a choice also undoes what it
+    // removed from the other layers.
+    pub fn step<R: Rng>(&mut self, rng: &mut R) -> bool {
+        let unsolved = (0..self.layers.len()).find(|layer| self.layers[*layer].render().is_none());
+
+        let layer = match unsolved {
+            Some(layer) => layer,
+            None => return false,
+        };
+
+        if self.allow_backtracking {
+            for field in &mut self.layers {
+                field.checkpoint();
+            }
+        }
+
+        let mut events = LayerEvents::default();
+
+        let stepped = self.layers[layer].step_observed(rng, &mut events);
+
+        let (x, y, potential_index) = match events.collapse {
+            Some(collapse) => collapse,
+            None => {
+                if self.allow_backtracking {
+                    self.rollback();
+                }
+
+                return false;
+            }
+        };
+
+        if stepped && !events.contradiction {
+            let mut changes = ChangeQueue::new();
+
+            for (changed_x, changed_y) in events.changed {
+                changes.add((layer, changed_x, changed_y));
+            }
+
+            if self.settle(changes) {
+                if self.allow_backtracking {
+                    self.history.push((layer, x, y, potential_index));
+                }
+
+                return true;
+            }
+        }
+
+        if self.allow_backtracking {
+            self.rollback();
+            self.ban(layer, x, y, potential_index)
+        } else {
+            false
+        }
+    }
+
+    // One rendering per layer, or None unless every layer is solved.
+    pub fn render(&self) -> Option<Vec<Vec<Vec<usize>>>> {
+        self.layers.iter().map(|layer| layer.render()).collect()
+    }
+
+    // Removes a choice that led to a contradiction.  If that contradicts too,
+    // the previous step is undone and its choice removed instead.
+    fn ban(
+        &mut self,
+        mut layer: usize,
+        mut x: usize,
+        mut y: usize,
+        mut potential_index: usize,
+    ) -> bool {
+        loop {
+            let allowed: Vec<usize> = self.layers[layer]
+                .allowed_potentials(x, y)
+                .into_iter()
+                .filter(|p| *p != potential_index)
+                .collect();
+
+            let mut changes = ChangeQueue::new();
+
+            if self.restrict_layer(layer, x, y, &allowed, &mut changes) && self.settle(changes) {
+                return true;
+            }
+
+            match self.history.pop() {
+                Some((previous_layer, previous_x, previous_y, previous_potential)) => {
+                    self.rollback();
+                    layer = previous_layer;
+                    x = previous_x;
+                    y = previous_y;
+                    potential_index = previous_potential;
+                }
+                None => return false,
+            }
+        }
+    }
+
+    // Undoes the last step in every layer, along with what it removed from
+    // the other layers.
+    fn rollback(&mut self) {
+        for field in &mut self.layers {
+            field.rollback();
+        }
+    }
+
+    // Applies the rules to every changed (layer, x, y) cell until nothing
+    // else changes.
+    fn settle(&mut self, mut changes: ChangeQueue<(usize, usize, usize)>) -> bool {
+        while let Some((layer, x, y)) = changes.next() {
+            if !self.layers[layer].is_active(x, y) {
+                continue;
+            }
+
+            for rule_index in 0..self.rules.len() {
+                let (other, allowed) = {
+                    let rule = &self.rules[rule_index];
+                    let field_a = &self.layers[rule.layer_a];
+                    let field_b = &self.layers[rule.layer_b];
+
+                    if rule.layer_a == layer {
+                        (rule.layer_b, rule.supported_b(field_a, field_b, x, y))
+                    } else if rule.layer_b == layer {
+                        (rule.layer_a, rule.supported_a(field_a, field_b, x, y))
+                    } else {
+                        continue;
+                    }
+                };
+
+                if self.layers[other].is_active(x, y)
+                    && !self.restrict_layer(other, x, y, &allowed, &mut changes)
+                {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn restrict_layer(
+        &mut self,
+        layer: usize,
+        x: usize,
+        y: usize,
+        allowed: &[usize],
+        changes: &mut ChangeQueue<(usize, usize, usize)>,
+    ) -> bool {
+        let field = &mut self.layers[layer];
+        let num_allowed = field.num_allowed(x, y);
+        let mut events = LayerEvents::default();
+
+        if !field.restrict_observed(x, y, allowed, &mut events) {
+            return false;
+        }
+
+        if field.num_allowed(x, y) != num_allowed {
+            changes.add((layer, x, y));
+        }
+
+        for (changed_x, changed_y) in events.changed {
+            changes.add((layer, changed_x, changed_y));
+        }
+
+        true
+    }
+}
+
+// Collects what happened to a layer while it was restricted or stepped: the
+// cells that lost potentials, the first cell collapsed, and whether anything
+// ran into a contradiction.
+#[derive(Default)]
+struct LayerEvents {
+    changed: Vec<(usize, usize)>,
+    collapse: Option<(usize, usize, usize)>,
+    contradiction: bool,
+}
+
+impl FieldObserver for LayerEvents {
+    fn on_collapse(&mut self, x: usize, y: usize, potential_index: usize) {
+        if self.collapse.is_none() {
+            self.collapse = Some((x, y, potential_index));
+        }
+
+        self.changed.push((x, y));
+    }
+
+    fn on_invalidate(&mut self, x: usize, y: usize, _potential_index: usize) {
+        self.changed.push((x, y));
+    }
+
+    fn on_contradiction(&mut self, _cell: Option<(usize, usize)>) {
+        self.contradiction = true;
+    }
+}
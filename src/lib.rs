@@ -2,7 +2,24 @@ extern crate rand;
 
 pub mod entry;
 pub mod field;
+pub mod solver;
+pub mod animation;
 pub mod boundary;
+pub mod chooser;
+pub mod chunk;
+pub mod connectivity;
+pub mod count;
+pub mod edge;
+pub mod footprint;
+pub mod hierarchy;
+pub mod layer;
+pub mod observer;
+pub mod render;
+pub mod selector;
+pub mod spacing;
+pub mod stats;
+pub mod symmetry;
+pub mod verify;
 
 mod containerutils;
 mod changequeue;
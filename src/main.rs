@@ -1,10 +1,19 @@
 extern crate rand;
 extern crate wfc;
 
+mod interactive;
+
+use std::env;
+
 use wfc::field::Field;
 use wfc::entry::CharacterEntry;
 use wfc::entry;
-use rand::Rng;
+use wfc::solver::ParallelSolver;
+use wfc::stats::Statistics;
+use wfc::edge::{EdgePolicy, Edges, Side};
+
+const CLOSE_EDGES: bool = false;
+const BUILD_ENTRANCE: bool = false;
 
 fn main() {
     //let characters = ['─', '┌', '┐', '│', '└', '┘', ' '];
@@ -33,82 +42,42 @@ fn execute() -> Result<(), String> {
 
     let mut field = Field::new(&potentials, 80, 40).allow_backtracking();
 
-    if true || field.close_edges() {
-        let mut rng = rand::thread_rng();
-
-        // Building an entrance requires the square pieces and space.
-        // If the space is missing, it will fail to force the potentials
-        if false {
-            // Set the entrance as 2 horizontal lines in the upper left.
-            if !field.force_potential(0, 5, 5) {
-                println!(
-                    "{}",
-                    entry::make_string(&potentials, &field.render_partial())
-                );
-                return Err(String::from("Failed to create entrance at (0,5)"));
-            }
-            if !field.force_potential(0, 6, 3) {
-                println!(
-                    "{}",
-                    entry::make_string(&potentials, &field.render_partial())
-                );
-                return Err(String::from("Failed to create entrance at (0,6)"));
-            }
-
-            // Set the exit as 2 horizontal lines in the lower right.
-            if !field.force_potential(79, 35, 4) {
-                println!(
-                    "{}",
-                    entry::make_string(&potentials, &field.render_partial())
-                );
-                return Err(String::from("Failed to create entrance at (79,35)"));
-            }
-            if !field.force_potential(79, 36, 2) {
-                println!(
-                    "{}",
-                    entry::make_string(&potentials, &field.render_partial())
-                );
-                return Err(String::from("Failed to create entrance at (79,36)"));
-            }
-        }
-
-        for i in 0..20 {
-            match run_field(&potentials, field.clone(), &mut rng) {
-                Ok(result) => {
-                    println!("Attemt {} succeeded :", i);
-                    println!("{}", result);
-                    break;
-                }
-
-                Err(result) => {
-                    println!("Attempt {} failed:", i);
-                    println!("{}", result);
-                }
-            }
-        }
-        Ok(())
+    let mut edges = Edges::new(if CLOSE_EDGES {
+        EdgePolicy::Closed
     } else {
-        Err(String::from("Could not close edges"))
-    }
-}
+        EdgePolicy::Free
+    });
 
-fn run_field<R: Rng>(
-    potentials: &[CharacterEntry],
-    mut field: Field,
-    mut rng: &mut R,
-) -> Result<String, String> {
-    loop {
-        if let Some(indices) = field.render() {
-            let result = entry::make_string(potentials, &indices);
+    if BUILD_ENTRANCE {
+        // Set the entrance in the upper left and the exit in the lower right.
+        edges = edges
+            .with_entrance(Side::West, 5)
+            .with_entrance(Side::East, 35);
+    }
 
-            return Ok(result);
-        }
+    if let Err(msg) = field.apply_edges(&edges) {
+        println!(
+            "{}",
+            entry::make_string(&potentials, &field.render_partial())
+        );
+        return Err(msg);
+    }
 
-        if field.step(&mut rng) == false {
-            let indices = field.render_partial();
-            let result = entry::make_string(potentials, &indices);
+    if env::args().any(|arg| arg == "--interactive") {
+        return interactive::run(&potentials, field);
+    }
 
-            return Err(result);
-        }
+    if env::args().any(|arg| arg == "--stats") {
+        print!("{}", Statistics::new(rand::random()).with_runs(20).collect(&field));
+        return Ok(());
     }
+
+    let solver = ParallelSolver::new(rand::random());
+
+    let solution = solver.solve(&field)?;
+
+    println!("Attempt {} succeeded :", solution.attempt);
+    println!("{}", entry::make_string(&potentials, &solution.indices));
+
+    Ok(())
 }
@@ -0,0 +1,28 @@
+// Receives events from Field while it solves.  Every method does nothing by
+// default, so observers only need to implement the events they care about.
+pub trait FieldObserver {
+    // A cell was collapsed to a single potential by Field::step.
+    fn on_collapse(&mut self, _x: usize, _y: usize, _potential_index: usize) {}
+
+    // Propagation removed a potential from a cell.
+    fn on_invalidate(&mut self, _x: usize, _y: usize, _potential_index: usize) {}
+
+    // A step that collapsed the cell to potential_index was undone, and that
+    // potential is no longer allowed there.
+    fn on_backtrack(&mut self, _x: usize, _y: usize, _potential_index: usize) {}
+
+    // Propagation left the field unsolvable.  The cell is given when a single
+    // cell ran out of potentials, and is None when a global constraint failed.
+    fn on_contradiction(&mut self, _cell: Option<(usize, usize)>) {}
+
+    // Asked by Field::solve_observed before every step.  Solving gives up,
+    // unsolved, once this returns true.
+    fn should_stop(&self) -> bool {
+        false
+    }
+}
+
+// An observer that ignores every event.
+pub struct NullObserver;
+
+impl FieldObserver for NullObserver {}
@@ -0,0 +1,100 @@
+use std::iter;
+
+use field::MASKED;
+
+// Turns the result of Field::render or Field::render_partial into something
+// displayable, using the entries the field was built from.
+pub trait Renderer<E> {
+    type Output;
+
+    fn render(&self, potentials: &[E], indices: &[Vec<usize>]) -> Self::Output;
+}
+
+// An entry that can be displayed as text.  The text may be more than one
+// character wide.
+pub trait TextEntry {
+    fn text(&self) -> String;
+}
+
+// Renders one line of text per row.  Every cell is padded with spaces to the
+// width of the widest text, so the columns line up.
+#[derive(Debug, Clone)]
+pub struct TextRenderer {
+    unknown: String,
+    contradiction: String,
+    masked: String,
+}
+
+impl Default for TextRenderer {
+    fn default() -> TextRenderer {
+        TextRenderer {
+            unknown: String::from("?"),
+            contradiction: String::from("!"),
+            masked: String::from(" "),
+        }
+    }
+}
+
+impl TextRenderer {
+    pub fn with_unknown(self, unknown: &str) -> TextRenderer {
+        TextRenderer {
+            unknown: unknown.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_contradiction(self, contradiction: &str) -> TextRenderer {
+        TextRenderer {
+            contradiction: contradiction.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_masked(self, masked: &str) -> TextRenderer {
+        TextRenderer {
+            masked: masked.to_string(),
+            ..self
+        }
+    }
+}
+
+impl<E: TextEntry> Renderer<E> for TextRenderer {
+    type Output = String;
+
+    fn render(&self, potentials: &[E], indices: &[Vec<usize>]) -> String {
+        let texts: Vec<String> = potentials.iter().map(|p| p.text()).collect();
+
+        let cell_width = texts
+            .iter()
+            .chain(&[
+                self.unknown.clone(),
+                self.contradiction.clone(),
+                self.masked.clone(),
+            ])
+            .map(|text| text.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut result = String::new();
+
+        for row in indices {
+            for i in row {
+                let text = if *i < texts.len() {
+                    &texts[*i]
+                } else if *i == texts.len() {
+                    &self.unknown
+                } else if *i == MASKED {
+                    &self.masked
+                } else {
+                    &self.contradiction
+                };
+
+                result.push_str(text);
+                result.extend(iter::repeat_n(' ', cell_width - text.chars().count()));
+            }
+            result.push('\n');
+        }
+
+        result
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt::Debug;
+
+use rand::Rng;
+
+use boundary::Direction;
+use field::Field;
+
+// Decides which cell Field::step collapses next.
+pub trait CellSelector: Debug + Send + Sync {
+    // Returns the (x, y) of a cell with more than one allowed potential, or
+    // None if there are no such cells left.
+    fn select(&self, field: &Field, rng: &mut dyn Rng) -> Option<(usize, usize)>;
+}
+
+// The cell with the lowest Shannon entropy over its weights, breaking ties at
+// random.  This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinEntropy;
+
+impl CellSelector for MinEntropy {
+    fn select(&self, field: &Field, rng: &mut dyn Rng) -> Option<(usize, usize)> {
+        select_min(field, rng, |x, y| Some(field.entropy(x, y)))
+    }
+}
+
+// The cell with the fewest allowed potentials, breaking ties at random.
+// Cheaper than MinEntropy, but ignores the weights.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinRemainingValues;
+
+impl CellSelector for MinRemainingValues {
+    fn select(&self, field: &Field, rng: &mut dyn Rng) -> Option<(usize, usize)> {
+        select_min(field, rng, |x, y| Some(field.num_allowed(x, y) as f32))
+    }
+}
+
+// The first cell in reading order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scanline;
+
+impl CellSelector for Scanline {
+    fn select(&self, field: &Field, _rng: &mut dyn Rng) -> Option<(usize, usize)> {
+        for y in 0..field.height() {
+            for x in 0..field.width() {
+                if field.num_allowed(x, y) > 1 {
+                    return Some((x, y));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Any cell, chosen uniformly at random.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomCell;
+
+impl CellSelector for RandomCell {
+    fn select(&self, field: &Field, rng: &mut dyn Rng) -> Option<(usize, usize)> {
+        select_min(field, rng, |_, _| Some(0.0))
+    }
+}
+
+// The lowest entropy cell next to an already collapsed cell, so the solution
+// grows outward from whatever has been decided.  Falls back to MinEntropy
+// when nothing borders a collapsed cell yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrowFromFrontier;
+
+impl CellSelector for GrowFromFrontier {
+    fn select(&self, field: &Field, rng: &mut dyn Rng) -> Option<(usize, usize)> {
+        let frontier = select_min(field, &mut *rng, |x, y| {
+            if borders_collapsed(field, x, y) {
+                Some(field.entropy(x, y))
+            } else {
+                None
+            }
+        });
+
+        frontier.or_else(|| MinEntropy.select(field, rng))
+    }
+}
+
+fn borders_collapsed(field: &Field, x: usize, y: usize) -> bool {
+    Direction::ALL_DIRECTIONS.iter().any(|direction| {
+        let (delta_x, delta_y) = direction.offset();
+        let test_x = x as isize + delta_x;
+        let test_y = y as isize + delta_y;
+
+        test_x >= 0 && test_y >= 0 && field.num_allowed(test_x as usize, test_y as usize) == 1
+    })
+}
+
+// Picks the undecided cell with the lowest score, skipping cells scored None.
+fn select_min<F>(field: &Field, mut rng: &mut dyn Rng, score: F) -> Option<(usize, usize)>
+where
+    F: Fn(usize, usize) -> Option<f32>,
+{
+    let mut result: Option<Candidate> = None;
+
+    for y in 0..field.height() {
+        for x in 0..field.width() {
+            if field.num_allowed(x, y) <= 1 {
+                continue;
+            }
+
+            if let Some(cell_score) = score(x, y) {
+                result = Some(match result {
+                    None => Candidate::new((x, y), cell_score),
+                    Some(best) => best.possibly_better((x, y), cell_score, &mut rng),
+                });
+            }
+        }
+    }
+
+    result.map(|candidate| candidate.cell)
+}
+
+struct Candidate {
+    cell: (usize, usize),
+    num_encountered: f32,
+    score: f32,
+}
+
+impl Candidate {
+    fn new(cell: (usize, usize), score: f32) -> Candidate {
+        Candidate {
+            cell,
+            score,
+            num_encountered: 1.0,
+        }
+    }
+
+    fn possibly_better<R: Rng>(
+        self,
+        new_cell: (usize, usize),
+        new_score: f32,
+        rng: &mut R,
+    ) -> Candidate {
+        let epsilon = 1e-6f32;
+
+        if new_score < self.score - epsilon {
+            // Always go for the lower score.
+            Candidate::new(new_cell, new_score)
+        } else if new_score > self.score + epsilon {
+            // Always reject higher score.
+            self
+        } else {
+            // They are nearly equal, use single pass fair selector.
+            let num_encountered = self.num_encountered + 1.0;
+
+            if rng.gen_range(0.0, num_encountered) < 1.0 {
+                Candidate {
+                    cell: new_cell,
+                    num_encountered,
+                    ..self
+                }
+            } else {
+                Candidate {
+                    num_encountered,
+                    ..self
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use rand;
+
+    #[test]
+    fn candidate_prefers_lower_score() {
+        let mut rng = rand::thread_rng();
+
+        let best = Candidate::new((0, 0), 2.0).possibly_better((1, 0), 1.0, &mut rng);
+        assert_eq!(best.cell, (1, 0));
+
+        let best = Candidate::new((0, 0), 1.0).possibly_better((1, 0), 2.0, &mut rng);
+        assert_eq!(best.cell, (0, 0));
+    }
+
+    #[test]
+    fn candidate_ties_are_fair() {
+        let mut rng = rand::thread_rng();
+        let mut counts = [0; 3];
+
+        for _ in 0..3000 {
+            let best = Candidate::new((0, 0), 1.0)
+                .possibly_better((1, 0), 1.0, &mut rng)
+                .possibly_better((2, 0), 1.0, &mut rng);
+
+            counts[best.cell.0] += 1;
+        }
+
+        for count in &counts {
+            assert!(*count > 800, "Unfair tie breaking: {:?}", counts);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use field::Field;
+use observer::FieldObserver;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub indices: Vec<Vec<usize>>,
+    // The result of Field::target_deviation for the solved field.
+    pub deviation: Vec<(usize, f32)>,
+    // Passing this to solve_attempt, with the same field and seed, reproduces
+    // the solution.
+    pub attempt: usize,
+}
+
+// Runs independent attempts at solving a field across several threads,
+// returning the first one to succeed.
+#[derive(Debug, Clone)]
+pub struct ParallelSolver {
+    seed: u64,
+    num_threads: usize,
+    max_attempts: usize,
+}
+
+impl ParallelSolver {
+    pub fn new(seed: u64) -> ParallelSolver {
+        ParallelSolver {
+            seed,
+            num_threads: thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            max_attempts: 20,
+        }
+    }
+
+    pub fn with_threads(self, num_threads: usize) -> ParallelSolver {
+        ParallelSolver {
+            num_threads: num_threads.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_max_attempts(self, max_attempts: usize) -> ParallelSolver {
+        ParallelSolver {
+            max_attempts,
+            ..self
+        }
+    }
+
+    pub fn solve(&self, field: &Field) -> Result<Solution, String> {
+        let next_attempt = AtomicUsize::new(0);
+        let solved = AtomicBool::new(false);
+        let solution = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..self.num_threads {
+                scope.spawn(|| loop {
+                    let attempt = next_attempt.fetch_add(1, Ordering::SeqCst);
+
+                    if attempt >= self.max_attempts || solved.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if let Some((indices, solved_field)) =
+                        run_attempt(field, self.seed, attempt, &solved)
+                    {
+                        let mut solution = solution.lock().unwrap();
+
+                        // Another thread may have finished at the same time,
+                        // keep whichever got here first.
+                        if solution.is_none() {
+                            *solution = Some(Solution {
+                                indices,
+                                deviation: solved_field.target_deviation(),
+                                attempt,
+                            });
+                        }
+
+                        solved.store(true, Ordering::SeqCst);
+                        break;
+                    }
+                });
+            }
+        });
+
+        solution.into_inner().unwrap().ok_or_else(|| {
+            format!("Failed to solve the field in {} attempts.", self.max_attempts)
+        })
+    }
+}
+
+// Runs a single attempt, exactly as the ParallelSolver with the same seed
+// would have run it.
+pub fn solve_attempt(field: &Field, seed: u64, attempt: usize) -> Option<Vec<Vec<usize>>> {
+    run_attempt(field, seed, attempt, &AtomicBool::new(false)).map(|(indices, _)| indices)
+}
+
+fn run_attempt(
+    field: &Field,
+    seed: u64,
+    attempt: usize,
+    cancel: &AtomicBool,
+) -> Option<(Vec<Vec<usize>>, Field)> {
+    let mut rng = Isaac64Rng::from_seed(&[seed, attempt as u64]);
+    let mut field = field.clone();
+
+    field
+        .solve_observed(&mut rng, &mut Cancel(cancel))
+        .map(|indices| (indices, field))
+}
+
+// Stops an attempt once another thread has solved the field.
+struct Cancel<'a>(&'a AtomicBool);
+
+impl<'a> FieldObserver for Cancel<'a> {
+    fn should_stop(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
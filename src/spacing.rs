@@ -0,0 +1,103 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    // The larger of the horizontal and vertical distances, so diagonal
+    // steps count as one.
+    Chebyshev,
+    // The horizontal distance plus the vertical distance.
+    Manhattan,
+}
+
+impl Metric {
+    pub fn distance(self, (x0, y0): (usize, usize), (x1, y1): (usize, usize)) -> usize {
+        let dx = x0.abs_diff(x1);
+        let dy = y0.abs_diff(y1);
+
+        match self {
+            Metric::Chebyshev => dx.max(dy),
+            Metric::Manhattan => dx + dy,
+        }
+    }
+}
+
+// Keeps cells holding any of one group of potentials at least min_distance
+// away from the cells holding any of another group.  The groups may be the
+// same, keeping its cells apart from each other.
+#[derive(Debug, Clone)]
+pub struct SpacingConstraint {
+    from: Vec<usize>,
+    to: Vec<usize>,
+    min_distance: usize,
+    metric: Metric,
+}
+
+impl SpacingConstraint {
+    pub fn apart(potentials: &[usize], min_distance: usize) -> SpacingConstraint {
+        SpacingConstraint::between(potentials, potentials, min_distance)
+    }
+
+    pub fn between(from: &[usize], to: &[usize], min_distance: usize) -> SpacingConstraint {
+        SpacingConstraint {
+            from: from.to_vec(),
+            to: to.to_vec(),
+            min_distance,
+            metric: Metric::Chebyshev,
+        }
+    }
+
+    pub fn with_metric(self, metric: Metric) -> SpacingConstraint {
+        SpacingConstraint { metric, ..self }
+    }
+
+    pub fn min_distance(&self) -> usize {
+        self.min_distance
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    // The potentials that may not be closer than min_distance to a cell
+    // holding potential_index.
+    pub fn excluded(&self, potential_index: usize) -> Vec<usize> {
+        let mut excluded = Vec::new();
+
+        if self.from.contains(&potential_index) {
+            excluded.extend_from_slice(&self.to);
+        }
+
+        if self.to.contains(&potential_index) {
+            for p in &self.from {
+                if !excluded.contains(p) {
+                    excluded.push(*p);
+                }
+            }
+        }
+
+        excluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn metric_distance() {
+        assert_eq!(Metric::Chebyshev.distance((1, 5), (4, 3)), 3);
+        assert_eq!(Metric::Manhattan.distance((1, 5), (4, 3)), 5);
+        assert_eq!(Metric::Manhattan.distance((2, 2), (2, 2)), 0);
+    }
+
+    #[test]
+    fn excluded_potentials() {
+        let apart = SpacingConstraint::apart(&[1, 2], 3);
+        assert_eq!(apart.excluded(1), vec![1, 2]);
+        assert!(apart.excluded(0).is_empty());
+
+        let between = SpacingConstraint::between(&[0], &[3], 2);
+        assert_eq!(between.excluded(0), vec![3]);
+        assert_eq!(between.excluded(3), vec![0]);
+        assert!(between.excluded(1).is_empty());
+    }
+}
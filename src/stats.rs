@@ -0,0 +1,141 @@
+use std::fmt;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use field::Field;
+use observer::FieldObserver;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PotentialStats {
+    // The fraction of cells the weights alone would give the potential.
+    pub expected_frequency: f32,
+    // The fraction of solved cells that held the potential.
+    pub observed_frequency: f32,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub runs: usize,
+    pub successes: usize,
+    pub mean_backtracks: f32,
+    pub potentials: Vec<PotentialStats>,
+}
+
+impl Report {
+    pub fn success_rate(&self) -> f32 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.successes as f32 / self.runs as f32
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} runs succeeded ({:.1}%), {:.2} backtracks per run",
+            self.successes,
+            self.runs,
+            self.success_rate() * 100.0,
+            self.mean_backtracks
+        )?;
+        writeln!(f, "potential  expected  observed     count")?;
+
+        for (potential_index, stats) in self.potentials.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>9}  {:>7.2}%  {:>7.2}%  {:>8}",
+                potential_index,
+                stats.expected_frequency * 100.0,
+                stats.observed_frequency * 100.0,
+                stats.count
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Solves a field many times, measuring how often each potential ends up in
+// the output compared to what its weight asks for.  Run n uses the same
+// random numbers as attempt n of a ParallelSolver with the same seed.
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    seed: u64,
+    runs: usize,
+}
+
+impl Statistics {
+    pub fn new(seed: u64) -> Statistics {
+        Statistics { seed, runs: 100 }
+    }
+
+    pub fn with_runs(self, runs: usize) -> Statistics {
+        Statistics { runs, ..self }
+    }
+
+    pub fn collect(&self, field: &Field) -> Report {
+        let num_potentials = field.num_potentials();
+        let mut counts = vec![0; num_potentials];
+        let mut successes = 0;
+        let mut backtracks = 0;
+
+        for run in 0..self.runs {
+            let mut rng = Isaac64Rng::from_seed(&[self.seed, run as u64]);
+            let mut counter = BacktrackCounter(0);
+
+            let solved = field.clone().solve_observed(&mut rng, &mut counter);
+
+            backtracks += counter.0;
+
+            if let Some(indices) = solved {
+                successes += 1;
+
+                for potential_index in indices.iter().flat_map(|row| row.iter()) {
+                    if *potential_index < num_potentials {
+                        counts[*potential_index] += 1;
+                    }
+                }
+            }
+        }
+
+        let total_weight: f32 = (0..num_potentials).map(|p| field.weight(p).max(0.0)).sum();
+        let total_count: usize = counts.iter().sum();
+
+        let potentials = counts
+            .into_iter()
+            .enumerate()
+            .map(|(potential_index, count)| PotentialStats {
+                expected_frequency: frequency(field.weight(potential_index).max(0.0), total_weight),
+                observed_frequency: frequency(count as f32, total_count as f32),
+                count,
+            })
+            .collect();
+
+        Report {
+            runs: self.runs,
+            successes,
+            mean_backtracks: frequency(backtracks as f32, self.runs as f32),
+            potentials,
+        }
+    }
+}
+
+fn frequency(amount: f32, total: f32) -> f32 {
+    if total > 0.0 {
+        amount / total
+    } else {
+        0.0
+    }
+}
+
+struct BacktrackCounter(usize);
+
+impl FieldObserver for BacktrackCounter {
+    fn on_backtrack(&mut self, _x: usize, _y: usize, _potential_index: usize) {
+        self.0 += 1;
+    }
+}
@@ -0,0 +1,31 @@
+use boundary::Boundary;
+
+// Ties every cell of the field to its image, which must hold the potential
+// whose boundary is the transformed boundary of the cell's potential.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    // Mirrored left to right.
+    Horizontal,
+    // Mirrored top to bottom.
+    Vertical,
+    // Turned half way around the center of the field.
+    Rotational,
+}
+
+impl Symmetry {
+    pub fn cell(self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Horizontal => (width - 1 - x, y),
+            Symmetry::Vertical => (x, height - 1 - y),
+            Symmetry::Rotational => (width - 1 - x, height - 1 - y),
+        }
+    }
+
+    pub fn boundary(self, boundary: &Boundary) -> Boundary {
+        match self {
+            Symmetry::Horizontal => boundary.mirror_horizontal(),
+            Symmetry::Vertical => boundary.mirror_vertical(),
+            Symmetry::Rotational => boundary.rotate_half(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use boundary::{Boundary, Direction};
+use entry::Entry;
+use field::MASKED;
+use footprint::{parts_fit, Part};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    // The cell holds something that is not one of the potentials.
+    Invalid {
+        x: usize,
+        y: usize,
+        potential_index: usize,
+    },
+    // The cell and its neighbor in direction do not fit together.
+    Neighbor {
+        x: usize,
+        y: usize,
+        direction: Direction,
+        potential_index: usize,
+        neighbor_potential: usize,
+    },
+    // The cell needs something in direction, which is outside of the grid or
+    // masked out.
+    Edge {
+        x: usize,
+        y: usize,
+        direction: Direction,
+        potential_index: usize,
+    },
+}
+
+// Checks complete grids, shaped like the output of Field::render, against the
+// same rules Field uses to build them.
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    parts: Vec<Part>,
+    boundaries: Vec<Boundary>,
+    close_edges: bool,
+}
+
+impl Verifier {
+    pub fn new<E: Entry>(potentials: &[E]) -> Verifier {
+        let footprints: Vec<(usize, usize)> =
+            potentials.iter().map(|entry| entry.footprint()).collect();
+        let parts = Part::split(&footprints);
+        let boundaries = parts
+            .iter()
+            .map(|part| potentials[part.entry].boundary().clone())
+            .collect();
+
+        Verifier {
+            parts,
+            boundaries,
+            close_edges: false,
+        }
+    }
+
+    // Also reports connections out of the grid or into masked out cells, as
+    // Field::close_edges and Field::close_mask_edges would have prevented.
+    pub fn with_closed_edges(self) -> Verifier {
+        Verifier {
+            close_edges: true,
+            ..self
+        }
+    }
+
+    // Returns every violation in the grid, reporting each pair of neighbors
+    // only once.  Masked out cells are not checked, and their neighbors treat
+    // them like the outside of the grid.
+    pub fn verify(&self, indices: &[Vec<usize>]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for (y, row) in indices.iter().enumerate() {
+            for (x, potential_index) in row.iter().enumerate() {
+                let potential_index = *potential_index;
+
+                if potential_index == MASKED {
+                    continue;
+                }
+
+                if potential_index >= self.parts.len() {
+                    violations.push(Violation::Invalid {
+                        x,
+                        y,
+                        potential_index,
+                    });
+                    continue;
+                }
+
+                for direction in &Direction::ALL_DIRECTIONS {
+                    match neighbor(indices, x, y, *direction) {
+                        Some(neighbor_potential) => {
+                            if neighbor_potential >= self.parts.len() || !is_forward(*direction) {
+                                continue;
+                            }
+
+                            if !parts_fit(
+                                &self.parts[potential_index],
+                                &self.boundaries[potential_index],
+                                &self.parts[neighbor_potential],
+                                &self.boundaries[neighbor_potential],
+                                *direction,
+                            ) {
+                                violations.push(Violation::Neighbor {
+                                    x,
+                                    y,
+                                    direction: *direction,
+                                    potential_index,
+                                    neighbor_potential,
+                                });
+                            }
+                        }
+
+                        None => {
+                            let part = &self.parts[potential_index];
+
+                            // A footprint can never reach outside of the grid
+                            // or into the mask, even with open edges.
+                            let escapes = !part.is_outer(*direction)
+                                || (self.close_edges
+                                    && self.boundaries[potential_index].requires(*direction));
+
+                            if escapes {
+                                violations.push(Violation::Edge {
+                                    x,
+                                    y,
+                                    direction: *direction,
+                                    potential_index,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+// Neighbor pairs are only checked from one side, the cell that comes first in
+// row major order.
+fn is_forward(direction: Direction) -> bool {
+    let (dx, dy) = direction.offset();
+    dy > 0 || (dy == 0 && dx > 0)
+}
+
+// The neighbor's potential, or None if it is outside of the grid or masked
+// out.
+fn neighbor(indices: &[Vec<usize>], x: usize, y: usize, direction: Direction) -> Option<usize> {
+    let (dx, dy) = direction.offset();
+    let test_x = x as isize + dx;
+    let test_y = y as isize + dy;
+
+    if test_x < 0 || test_y < 0 {
+        return None;
+    }
+
+    indices
+        .get(test_y as usize)
+        .and_then(|row| row.get(test_x as usize))
+        .cloned()
+        .filter(|potential_index| *potential_index != MASKED)
+}
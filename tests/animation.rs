@@ -0,0 +1,80 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::Isaac64Rng;
+
+use wfc::animation::{GifRecorder, Tile};
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+
+#[test]
+fn record_gif() {
+    let potentials = [
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let tiles = (0..potentials.len())
+        .map(|i| Tile::solid([i as u8 * 30, 0, 255 - i as u8 * 30], 2, 2))
+        .collect();
+
+    let mut recorder = GifRecorder::new(tiles, 2, 2).with_stride(4);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    let mut field = Field::new(&potentials, 10, 6).allow_backtracking();
+
+    let mut num_steps = 0;
+    while field.render().is_none() {
+        assert!(field.step_observed(&mut rng, &mut recorder));
+        recorder.record(&field).unwrap();
+        num_steps += 1;
+    }
+
+    recorder.record_frame(&field).unwrap();
+
+    assert!(recorder.num_frames() > num_steps / 4);
+    assert!(recorder.num_frames() <= num_steps + 1);
+
+    let mut gif = Vec::new();
+    recorder.write(&mut gif).unwrap();
+
+    assert_eq!(&gif[0..6], b"GIF89a");
+    assert_eq!(&gif[6..10], &[20, 0, 12, 0]);
+    assert_eq!(gif.last(), Some(&0x3b));
+}
+
+#[test]
+fn record_gif_too_many_colors() {
+    let tiles = vec![Tile::from_bitmap(
+        (0..300).map(|i| [(i % 256) as u8, (i / 256) as u8, 0]).collect(),
+    )];
+
+    let potentials = [CharacterEntry::build(' ', 1.0, "000|000|000").unwrap()];
+    let field = Field::new(&potentials, 1, 1);
+
+    let mut recorder = GifRecorder::new(tiles, 20, 15);
+    recorder.record_frame(&field).unwrap();
+
+    assert!(recorder.write(&mut Vec::new()).is_err());
+}
+
+#[test]
+fn record_gif_wrong_number_of_tiles() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+    let field = Field::new(&potentials, 2, 2);
+
+    let tiles = vec![Tile::solid([0, 0, 0], 2, 2)];
+    let mut recorder = GifRecorder::new(tiles, 2, 2);
+
+    assert!(recorder.record(&field).is_err());
+    assert!(recorder.record_frame(&field).is_err());
+    assert_eq!(recorder.num_frames(), 0);
+}
@@ -76,7 +76,7 @@ fn build_from_str_fails_long() {
 }
 
 #[test]
-#[should_panic(expected = "\\'b\\'")]
+#[should_panic(expected = "'b'")]
 fn build_from_str_fails_unknown_char() {
     let source = "010|00b|000";
 
@@ -0,0 +1,184 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::Isaac64Rng;
+
+use wfc::boundary::Direction;
+use wfc::chooser::{ChooserFn, LeastConstraining, MaxWeight, ValueChooser, WeightedRandom};
+use wfc::entry;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::selector::Scanline;
+
+fn solve<C: ValueChooser + 'static>(potentials: &[CharacterEntry], chooser: C) -> String {
+    let mut field = Field::new(potentials, 3, 2)
+        .with_selector(Scanline)
+        .with_chooser(chooser);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    entry::make_string(potentials, &field.render().unwrap())
+}
+
+#[test]
+fn weighted_random_chooses_allowed() {
+    let potentials = [
+        CharacterEntry::build('a', 0.1, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 0.2, "000|000|000").unwrap(),
+        CharacterEntry::build('c', 0.3, "000|000|000").unwrap(),
+        CharacterEntry::build('d', 10.1, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 1, 1);
+    assert!(field.restrict(0, 0, &[0, 2]));
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    for _ in 0..20 {
+        let chosen_index = WeightedRandom.choose(&field, 0, 0, &mut rng).unwrap();
+        assert!(chosen_index == 0 || chosen_index == 2);
+    }
+}
+
+#[test]
+fn max_weight_is_deterministic() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 3.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 2.0, "000|000|000").unwrap(),
+    ];
+
+    assert_eq!(solve(&potentials, MaxWeight), "###\n###\n");
+}
+
+#[test]
+fn least_constraining_avoids_connections() {
+    // In the corner, each line piece rules out three of its neighbors'
+    // options, while the space only rules out two.
+    let potentials = [
+        CharacterEntry::build('-', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('|', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 2)
+        .with_selector(Scanline)
+        .with_chooser(LeastConstraining);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    assert!(field.step(&mut rng));
+
+    assert_eq!(field.render_partial()[0][0], 2);
+}
+
+#[test]
+fn callback_receives_cell_and_allowed() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let checkerboard = ChooserFn::new(|x, y, allowed: &[usize]| {
+        assert_eq!(allowed, &[0, 1]);
+        Some((x + y) % 2)
+    });
+
+    assert_eq!(solve(&potentials, checkerboard), ".#.\n#.#\n");
+}
+
+#[test]
+fn chooser_fn_bad_choice_fails_step() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    // A potential the cell no longer allows.
+    let mut field = Field::new(&potentials, 1, 1)
+        .allow_backtracking()
+        .with_chooser(ChooserFn::new(|_, _, _: &[usize]| Some(2)));
+    assert!(field.restrict(0, 0, &[0, 1]));
+    assert!(!field.step(&mut rng));
+    assert_eq!(field.num_allowed(0, 0), 2);
+
+    // A potential that does not exist.
+    let mut field = Field::new(&potentials, 1, 1)
+        .with_chooser(ChooserFn::new(|_, _, _: &[usize]| Some(7)));
+    assert!(!field.step(&mut rng));
+}
+
+#[test]
+fn preference_scales_weight() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 2.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 1)
+        .with_preference(0, Direction::West, 1, 4.0)
+        .with_preference(1, Direction::West, 1, 0.5);
+
+    assert_eq!(field.weight_at(1, 0, 0), 1.0);
+    assert!(field.force_potential(0, 0, 1));
+    assert_eq!(field.weight_at(1, 0, 0), 4.0);
+    assert_eq!(field.weight_at(1, 0, 1), 1.0);
+    assert_eq!(field.weight_at(0, 0, 1), 2.0);
+}
+
+#[test]
+fn preference_changes_max_weight() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 2.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 1)
+        .with_selector(Scanline)
+        .with_chooser(MaxWeight)
+        .with_preference(0, Direction::West, 1, 4.0);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    while field.step(&mut rng) {}
+
+    assert_eq!(entry::make_string(&potentials, &field.render().unwrap()), "bab\n");
+}
+
+#[test]
+fn preference_encourages_runs() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let count_runs = |field: Field| {
+        let mut field = field.with_selector(Scanline);
+        let mut rng = Isaac64Rng::new_unseeded();
+
+        while field.step(&mut rng) {}
+
+        field
+            .render()
+            .unwrap()
+            .iter()
+            .map(|row| row.windows(2).filter(|pair| pair[0] == pair[1]).count())
+            .sum::<usize>()
+    };
+
+    let plain = count_runs(Field::new(&potentials, 20, 10));
+    let preferred = count_runs(
+        Field::new(&potentials, 20, 10)
+            .with_preference(0, Direction::West, 0, 20.0)
+            .with_preference(1, Direction::West, 1, 20.0),
+    );
+
+    assert!(preferred > plain);
+    assert!(preferred > 170);
+}
@@ -0,0 +1,147 @@
+extern crate wfc;
+
+use wfc::boundary::Direction;
+use wfc::chunk::ChunkManager;
+use wfc::entry::{CharacterEntry, Entry};
+use wfc::field::Field;
+
+fn box_potentials() -> Vec<CharacterEntry> {
+    vec![
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ]
+}
+
+#[test]
+fn chunk_seams_fit() {
+    let potentials = box_potentials();
+    let prototype = Field::new(&potentials, 6, 4).allow_backtracking();
+
+    let mut chunks = ChunkManager::new(prototype, 7);
+
+    // Check every horizontal and vertical neighbor pair of a 3x3 block of
+    // chunks, generated in an awkward order.
+    for &(chunk_x, chunk_y) in &[(0, 0), (-1, -1), (1, 0), (0, -1), (-1, 0), (1, 1), (0, 1)] {
+        chunks.chunk(chunk_x, chunk_y).unwrap();
+    }
+
+    for y in -4..8i64 {
+        for x in -6..12i64 {
+            let source = chunks.cell(x, y).unwrap();
+
+            for &(direction, delta_x, delta_y) in
+                &[(Direction::East, 1, 0), (Direction::South, 0, 1)]
+            {
+                let test = chunks.cell(x + delta_x, y + delta_y).unwrap();
+
+                assert!(
+                    potentials[source]
+                        .boundary()
+                        .fits(potentials[test].boundary(), direction),
+                    "({}, {}) does not fit its {:?} neighbor",
+                    x,
+                    y,
+                    direction
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn chunk_seams_fit_north_west() {
+    let potentials = box_potentials();
+    let prototype = Field::new(&potentials, 6, 4).allow_backtracking();
+
+    let mut chunks = ChunkManager::new(prototype, 5);
+
+    // The chunks to the north and west are generated after the center, so
+    // their south and east borders are the ones being constrained.
+    for &(chunk_x, chunk_y) in &[(0, 0), (0, -1), (-1, 0)] {
+        chunks.chunk(chunk_x, chunk_y).unwrap();
+    }
+
+    for x in 0..6i64 {
+        let source = chunks.cell(x, 0).unwrap();
+        let test = chunks.cell(x, -1).unwrap();
+
+        assert!(
+            potentials[source]
+                .boundary()
+                .fits(potentials[test].boundary(), Direction::North),
+            "({}, 0) does not fit its North neighbor",
+            x
+        );
+    }
+
+    for y in 0..4i64 {
+        let source = chunks.cell(0, y).unwrap();
+        let test = chunks.cell(-1, y).unwrap();
+
+        assert!(
+            potentials[source]
+                .boundary()
+                .fits(potentials[test].boundary(), Direction::West),
+            "(0, {}) does not fit its West neighbor",
+            y
+        );
+    }
+}
+
+#[test]
+fn chunk_seams_fit_diagonally() {
+    // Only the corners connect, so the diagonal neighbors are the only
+    // constraint between chunks.
+    let potentials = vec![
+        CharacterEntry::build('#', 1.0, "101|000|101").unwrap(),
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+    ];
+    let prototype = Field::new(&potentials, 3, 3).allow_backtracking();
+
+    for seed in 0..8 {
+        let mut chunks = ChunkManager::new(prototype.clone(), seed);
+
+        // None of these chunks share a side, only a corner with the center.
+        for &(chunk_x, chunk_y) in &[(0, 0), (1, 1), (-1, -1), (1, -1), (-1, 1)] {
+            chunks.chunk(chunk_x, chunk_y).unwrap();
+        }
+
+        for &(x, y, direction, test_x, test_y) in &[
+            (2, 2, Direction::SouthEast, 3, 3),
+            (0, 0, Direction::NorthWest, -1, -1),
+            (2, 0, Direction::NorthEast, 3, -1),
+            (0, 2, Direction::SouthWest, -1, 3),
+        ] {
+            let source = chunks.cell(x, y).unwrap();
+            let test = chunks.cell(test_x, test_y).unwrap();
+
+            assert!(
+                potentials[source]
+                    .boundary()
+                    .fits(potentials[test].boundary(), direction),
+                "seed {}: ({}, {}) does not fit its {:?} neighbor",
+                seed,
+                x,
+                y,
+                direction
+            );
+        }
+    }
+}
+
+#[test]
+fn chunk_is_deterministic() {
+    let potentials = box_potentials();
+    let prototype = Field::new(&potentials, 6, 4).allow_backtracking();
+
+    let mut chunks_a = ChunkManager::new(prototype.clone(), 11);
+    let mut chunks_b = ChunkManager::new(prototype, 11);
+
+    assert_eq!(chunks_a.chunk(3, -2).unwrap(), chunks_b.chunk(3, -2).unwrap());
+    assert!(chunks_a.get(0, 0).is_none());
+}
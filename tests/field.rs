@@ -1,9 +1,15 @@
 extern crate rand;
 extern crate wfc;
 
+use rand::Isaac64Rng;
+
+use wfc::connectivity::Connectivity;
+use wfc::count::CountConstraint;
+use wfc::edge::{EdgePolicy, Edges, Side};
 use wfc::entry;
 use wfc::entry::CharacterEntry;
 use wfc::field::Field;
+use wfc::spacing::{Metric, SpacingConstraint};
 
 #[test]
 fn simple_field_propagate_fail() {
@@ -13,7 +19,19 @@ fn simple_field_propagate_fail() {
     ];
 
     let mut field = Field::new(&potentials, 2, 2);
-    assert_eq!(field.force_potential(0, 0, 1), false);
+    assert!(!field.force_potential(0, 0, 1));
+}
+
+#[test]
+fn wide_field_propagate_north_east() {
+    let potentials = [
+        CharacterEntry::build('╱', 1.0, "001|000|100").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 2);
+    assert!(field.force_potential(1, 1, 0));
+    assert_eq!(field.render_partial()[0][2], 0);
 }
 
 #[test]
@@ -53,7 +71,7 @@ fn simple_field_closed_edges_fail() {
     ];
 
     let mut field = Field::new(&potentials, 2, 2);
-    assert_eq!(field.close_edges(), false);
+    assert!(!field.close_edges());
 }
 
 #[test]
@@ -98,3 +116,628 @@ fn simple_field_closed_edges2() {
         panic!("Field did not fully close.");
     }
 }
+
+#[test]
+fn masked_field_closed_edges() {
+    let potentials = [
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    let mask = vec![vec![true, true, false], vec![true, true, false]];
+
+    let mut field = Field::new(&potentials, 3, 2).with_mask(&mask).unwrap();
+
+    assert!(!field.is_active(2, 0));
+    assert!(!field.force_potential(2, 1, 0));
+
+    assert!(field.close_edges());
+    assert!(field.close_mask_edges());
+
+    if let Some(result) = field.render() {
+        let expected = "┌┐ \n└┘ \n";
+        let result_str = entry::make_string(&potentials, &result);
+        assert_eq!(result_str, expected);
+    } else {
+        panic!("Masked field did not fully close.");
+    }
+}
+
+#[test]
+fn masked_field_wrong_size() {
+    let potentials = [CharacterEntry::build(' ', 1.0, "000|000|000").unwrap()];
+
+    let mask = vec![vec![true, true], vec![true]];
+
+    assert!(Field::new(&potentials, 2, 2).with_mask(&mask).is_err());
+}
+
+#[test]
+fn restrict_propagates() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build('|', 1.0, "010|000|010").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 1);
+
+    // Neither piece may point outside of the field, so restricting
+    // to '-' or '|' removes every option from the left cell.
+    assert!(field.close_edges());
+    assert!(!field.restrict(0, 0, &[0, 1]));
+}
+
+#[test]
+fn restrict_region_and_ban() {
+    let potentials = [
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 2);
+
+    assert!(field.restrict_region(0, 0, 2, 1, &[0, 1]));
+    assert_eq!(
+        entry::make_string(&potentials, &field.render_partial()),
+        "??\n??\n"
+    );
+
+    assert!(field.ban(0, 0, 1));
+    assert_eq!(
+        entry::make_string(&potentials, &field.render_partial()),
+        "┌┐\n??\n"
+    );
+}
+
+#[test]
+fn edges_with_entrance() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 1);
+
+    let edges = Edges::new(EdgePolicy::Closed)
+        .with_entrance(Side::West, 0)
+        .with_entrance(Side::East, 0);
+
+    assert!(field.apply_edges(&edges).is_ok());
+
+    if let Some(result) = field.render() {
+        let result_str = entry::make_string(&potentials, &result);
+        assert_eq!(result_str, "---\n");
+    } else {
+        panic!("Field did not fully propagate.");
+    }
+}
+
+#[test]
+fn edges_with_entrance_fail() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 1);
+
+    let edges = Edges::new(EdgePolicy::Closed).with_entrance(Side::West, 0);
+
+    assert!(field.apply_edges(&edges).is_err());
+}
+
+#[test]
+fn edges_entrance_out_of_range() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 2);
+
+    let edges = Edges::new(EdgePolicy::Free).with_entrance(Side::West, 2);
+
+    assert!(field.apply_edges(&edges).is_err());
+    assert_eq!(field.allowed_potentials(0, 1), vec![0, 1]);
+
+    let edges = Edges::new(EdgePolicy::Free).with_entrance(Side::West, 1);
+
+    assert!(field.apply_edges(&edges).is_ok());
+}
+
+#[test]
+fn edges_per_side() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build('|', 1.0, "010|000|010").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 2);
+
+    let edges = Edges::new(EdgePolicy::Free)
+        .with_side(Side::North, EdgePolicy::Open)
+        .with_side(Side::South, EdgePolicy::OneOf(vec![1]));
+
+    assert!(field.apply_edges(&edges).is_ok());
+
+    if let Some(result) = field.render() {
+        let result_str = entry::make_string(&potentials, &result);
+        assert_eq!(result_str, "||\n||\n");
+    } else {
+        panic!("Field did not fully propagate.");
+    }
+}
+
+#[test]
+fn count_constraint_exactly() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('$', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    for _ in 0..10 {
+        let mut field = Field::new(&potentials, 4, 4)
+            .with_count_constraint(CountConstraint::exactly(&[1], 1))
+            .allow_backtracking();
+
+        while field.render().is_none() {
+            assert!(field.step(&mut rng));
+        }
+
+        let result = field.render().unwrap();
+        let num_treasure = result.iter().flat_map(|row| row.iter()).filter(|i| **i == 1).count();
+        assert_eq!(num_treasure, 1);
+    }
+}
+
+#[test]
+fn count_constraint_at_least_forces() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 2)
+        .with_count_constraint(CountConstraint::at_least_fraction(&[1], 0.5));
+
+    assert!(field.force_potential(0, 0, 0));
+    assert!(field.force_potential(1, 0, 0));
+
+    assert_eq!(
+        entry::make_string(&potentials, &field.render().unwrap()),
+        "..\n~~\n"
+    );
+
+    assert!(!field.force_potential(0, 1, 0));
+}
+
+#[test]
+fn connectivity_all_connected() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build('╶', 1.0, "000|001|000").unwrap(),
+        CharacterEntry::build('╴', 1.0, "000|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 5, 1).with_connectivity(Connectivity::AllConnected);
+
+    assert!(field.close_edges());
+    assert!(field.force_potential(0, 0, 1));
+    assert!(field.force_potential(4, 0, 2));
+
+    // A gap in the middle would leave two separate pieces.
+    let mut gap_field = field.clone();
+    assert!(!gap_field.force_potential(2, 0, 3));
+
+    assert!(field.force_potential(2, 0, 0));
+}
+
+#[test]
+fn connectivity_connected_cells() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build('╶', 1.0, "000|001|000").unwrap(),
+        CharacterEntry::build('╴', 1.0, "000|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field =
+        Field::new(&potentials, 5, 1).with_connectivity(Connectivity::Connected((0, 0), (4, 0)));
+
+    assert!(field.close_edges());
+    assert!(!field.clone().force_potential(0, 0, 3));
+    assert!(!field.force_potential(3, 0, 2));
+}
+
+#[test]
+fn inpaint_keeps_outside() {
+    let potentials = [
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut prototype = Field::new(&potentials, 8, 6).allow_backtracking();
+    assert!(prototype.close_edges());
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    let mut field = prototype.clone();
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+    let original = field.render().unwrap();
+
+    let mut inpainted = prototype.clone();
+    assert!(inpainted.inpaint_region(&original, 2, 1, 4, 4));
+
+    let partial = inpainted.render_partial();
+    for y in 0..6 {
+        for x in 0..8 {
+            let inside = (2..6).contains(&x) && (1..5).contains(&y);
+            if !inside {
+                assert_eq!(partial[y][x], original[y][x]);
+            }
+        }
+    }
+
+    while inpainted.render().is_none() {
+        assert!(inpainted.step(&mut rng));
+    }
+
+    let result = inpainted.render().unwrap();
+    assert_eq!(result[0], original[0]);
+    assert_eq!(result[5], original[5]);
+}
+
+#[test]
+fn inpaint_reopens_solved_field() {
+    let potentials = [
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 8, 6).allow_backtracking();
+    assert!(field.close_edges());
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+    let original = field.render().unwrap();
+
+    // Inpaint the solved field itself, not a fresh copy.
+    assert!(field.inpaint_region(&original, 2, 1, 4, 4));
+
+    assert!(field.render().is_none());
+    for y in 2..4 {
+        for x in 3..5 {
+            assert!(field.num_allowed(x, y) > 1, "({}, {}) was not reopened", x, y);
+        }
+    }
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let result = field.render().unwrap();
+    for y in 0..6 {
+        for x in 0..8 {
+            let inside = (2..6).contains(&x) && (1..5).contains(&y);
+            if !inside {
+                assert_eq!(result[y][x], original[y][x]);
+            }
+        }
+    }
+}
+
+#[test]
+fn inpaint_twice() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('c', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 4, 4);
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let first = field.render().unwrap();
+    assert!(field.inpaint_region(&first, 0, 0, 2, 2));
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    // The cells locked by the first inpaint are reopened by the second.
+    let second = field.render().unwrap();
+    assert!(field.inpaint_region(&second, 2, 2, 2, 2));
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if x >= 2 && y >= 2 { 3 } else { 1 };
+            assert_eq!(field.num_allowed(x, y), expected, "({}, {})", x, y);
+        }
+    }
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let result = field.render().unwrap();
+    for y in 0..2 {
+        assert_eq!(result[y], second[y]);
+    }
+}
+
+#[test]
+fn inpaint_rejects_wrong_shape() {
+    let potentials = [CharacterEntry::build(' ', 1.0, "000|000|000").unwrap()];
+
+    let mut field = Field::new(&potentials, 2, 2);
+
+    assert!(!field.inpaint_region(&[vec![0, 0]], 0, 0, 1, 1));
+}
+
+#[test]
+fn load_partial_string() {
+    let potentials = [
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 2);
+
+    assert!(entry::load_string(&mut field, &potentials, "┌?\n?┘\n").is_ok());
+    assert_eq!(
+        entry::make_string(&potentials, &field.render().unwrap()),
+        "┌┐\n└┘\n"
+    );
+}
+
+#[test]
+fn load_partial_string_conflict() {
+    let potentials = [
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    let cells = entry::parse_string(&potentials, "┌?\n┌?\n").unwrap();
+    assert_eq!(cells, vec![vec![Some(0), None], vec![Some(0), None]]);
+
+    let mut field = Field::new(&potentials, 2, 2);
+    assert_eq!(field.force_potentials(&cells), Err((0, 1)));
+
+    let error = entry::parse_string(&potentials, "┌?\n?x\n").unwrap_err();
+    assert!(error.contains("(1, 1)"));
+}
+
+#[test]
+fn load_string_wrong_size() {
+    let potentials = [
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    for text in &["┌??\n?┘?\n", "┌?\n?┘\n??\n", "┌?\n"] {
+        let mut field = Field::new(&potentials, 2, 2);
+
+        let error = entry::load_string(&mut field, &potentials, text).unwrap_err();
+        assert!(error.contains("wrong size"), "{}", error);
+    }
+}
+
+#[test]
+fn target_frequency_is_matched() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 20, 20)
+        .with_target_frequency(0, 0.3)
+        .with_target_frequency(1, 0.1);
+
+    assert_eq!(field.target_deviation(), vec![(0, -0.3), (1, -0.1)]);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.step(&mut rng) {}
+
+    let histogram = field.histogram();
+    assert_eq!(histogram.iter().sum::<usize>(), 400);
+
+    for (_, deviation) in field.target_deviation() {
+        assert!(deviation.abs() < 0.01, "deviation {}", deviation);
+    }
+}
+
+#[test]
+fn target_frequency_is_replaced() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 20, 20)
+        .with_target_frequency(0, 0.9)
+        .with_target_frequency(1, 0.1)
+        .with_target_frequency(0, 0.3);
+
+    assert_eq!(field.target_deviation(), vec![(0, -0.3), (1, -0.1)]);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.step(&mut rng) {}
+
+    // The other potential gets what the replaced target no longer asks for.
+    let histogram = field.histogram();
+    assert!(histogram[2] > 200, "histogram {:?}", histogram);
+
+    for (_, deviation) in field.target_deviation() {
+        assert!(deviation.abs() < 0.01, "deviation {}", deviation);
+    }
+}
+
+#[test]
+fn target_frequency_leaves_others_choosable() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    // The target leaves nothing for the other potentials, but the first cell
+    // can only hold one of them.
+    let mut field = Field::new(&potentials, 3, 1)
+        .with_target_frequency(0, 1.0)
+        .allow_backtracking();
+    assert!(field.restrict(0, 0, &[1, 2]));
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let result = field.render().unwrap();
+    assert_ne!(result[0][0], 0);
+    assert_eq!(&result[0][1..], &[0, 0]);
+}
+
+#[test]
+fn spacing_bans_nearby_cells() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('S', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('D', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 9, 9)
+        .with_spacing(SpacingConstraint::apart(&[1], 3))
+        .with_spacing(SpacingConstraint::between(&[1], &[2], 2).with_metric(Metric::Manhattan));
+
+    assert!(field.force_potential(4, 4, 1));
+
+    assert_eq!(field.allowed_potentials(6, 6), vec![0, 2]);
+    assert_eq!(field.allowed_potentials(6, 5), vec![0, 2]);
+    assert_eq!(field.allowed_potentials(5, 5), vec![0, 2]);
+    assert_eq!(field.allowed_potentials(5, 4), vec![0]);
+    assert_eq!(field.allowed_potentials(7, 4), vec![0, 1, 2]);
+
+    assert!(!field.force_potential(3, 4, 2));
+}
+
+#[test]
+fn spacing_holds_in_solution() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('S', 5.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 16, 12)
+        .with_spacing(SpacingConstraint::apart(&[1], 4).with_metric(Metric::Manhattan));
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.step(&mut rng) {}
+
+    let indices = field.render().unwrap();
+    let mut spawns = Vec::new();
+
+    for (y, row) in indices.iter().enumerate() {
+        for (x, potential_index) in row.iter().enumerate() {
+            if *potential_index == 1 {
+                spawns.push((x, y));
+            }
+        }
+    }
+
+    assert!(spawns.len() > 5);
+
+    for (i, a) in spawns.iter().enumerate() {
+        for b in &spawns[i + 1..] {
+            assert!(Metric::Manhattan.distance(*a, *b) >= 4);
+        }
+    }
+}
+
+#[test]
+fn enumerate_all_solutions() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 2, 2);
+
+    assert_eq!(field.count_solutions(100), 16);
+    assert_eq!(field.count_solutions(10), 10);
+    assert_eq!(field.count_solutions(0), 0);
+
+    let solutions = field.solutions(100);
+    assert_eq!(solutions.len(), 16);
+
+    for (i, solution) in solutions.iter().enumerate() {
+        assert!(!solutions[i + 1..].contains(solution));
+    }
+
+    assert!(field.force_potential(0, 0, 1));
+    assert_eq!(field.count_solutions(100), 8);
+    assert!(field.solutions(100).iter().all(|solution| solution[0][0] == 1));
+}
+
+#[test]
+fn enumerate_respects_constraints() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let field = Field::new(&potentials, 3, 1);
+    assert_eq!(field.solutions(10), vec![vec![vec![0, 0, 0]], vec![vec![1, 1, 1]]]);
+
+    let mut closed = field.clone();
+    assert!(closed.close_edges());
+    assert_eq!(closed.solutions(10), vec![vec![vec![1, 1, 1]]]);
+
+    let counted = Field::new(&potentials, 4, 1)
+        .with_count_constraint(CountConstraint::exactly(&[0], 4));
+    assert_eq!(counted.count_solutions(10), 1);
+
+    let mut visited = 0;
+    assert_eq!(
+        field.enumerate_solutions(|_| {
+            visited += 1;
+            false
+        }),
+        1
+    );
+    assert_eq!(visited, 1);
+}
@@ -0,0 +1,175 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use wfc::boundary::Boundary;
+use wfc::chooser::LeastConstraining;
+use wfc::entry::Entry;
+use wfc::field::Field;
+use wfc::selector::Scanline;
+
+struct Piece {
+    boundary: Boundary,
+    footprint: (usize, usize),
+    weight: f32,
+}
+
+impl Piece {
+    fn new(borders: &str, width: usize, height: usize) -> Piece {
+        Piece {
+            boundary: Boundary::from_str(borders).unwrap(),
+            footprint: (width, height),
+            weight: 1.0,
+        }
+    }
+
+    fn with_weight(self, weight: f32) -> Piece {
+        Piece { weight, ..self }
+    }
+}
+
+impl Entry for Piece {
+    fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    fn boundary(&self) -> &Boundary {
+        &self.boundary
+    }
+
+    fn footprint(&self) -> (usize, usize) {
+        self.footprint
+    }
+}
+
+#[test]
+fn footprints_are_filled() {
+    let pieces = [
+        Piece::new("000|000|000", 1, 1),
+        Piece::new("000|000|000", 2, 2),
+        Piece::new("000|000|000", 3, 1),
+    ];
+
+    for seed in 0..10 {
+        let mut field = Field::new(&pieces, 6, 5);
+        let indices = field.solve(&mut Isaac64Rng::from_seed(&[seed])).unwrap();
+
+        for (y, row) in indices.iter().enumerate() {
+            for (x, potential_index) in row.iter().enumerate() {
+                let part = *field.part(*potential_index);
+                let (left, top) = (x - part.x, y - part.y);
+
+                for fy in 0..part.height {
+                    for fx in 0..part.width {
+                        let covered = *field.part(indices[top + fy][left + fx]);
+                        assert_eq!(covered.entry, part.entry);
+                        assert_eq!((covered.x, covered.y), (fx, fy));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn footprint_must_fit_field() {
+    let pieces = [
+        Piece::new("000|000|000", 1, 1),
+        Piece::new("000|000|000", 3, 1),
+    ];
+
+    let field = Field::new(&pieces, 2, 2);
+
+    assert_eq!(field.num_potentials(), 4);
+    assert_eq!(field.allowed_potentials(0, 0), vec![0]);
+    assert_eq!(field.allowed_potentials(1, 1), vec![0]);
+}
+
+#[test]
+fn footprint_must_fit_mask() {
+    let pieces = [
+        Piece::new("000|000|000", 1, 1),
+        Piece::new("000|000|000", 2, 1),
+    ];
+
+    let field = Field::new(&pieces, 3, 1)
+        .with_mask(&[vec![true, true, false]])
+        .unwrap();
+
+    assert_eq!(field.allowed_potentials(0, 0), vec![0, 1]);
+    assert_eq!(field.allowed_potentials(1, 0), vec![0, 2]);
+}
+
+#[test]
+fn boundary_applies_to_outer_edges() {
+    let pieces = [
+        Piece::new("000|000|000", 1, 1),
+        Piece::new("000|101|000", 1, 1),
+        Piece::new("000|101|000", 2, 1),
+    ];
+
+    let mut field = Field::new(&pieces, 4, 1);
+
+    // The left half of the wide piece connects west, but not east into its
+    // other half.
+    assert!(field.force_potential(1, 0, 2));
+    assert_eq!(field.allowed_potentials(0, 0), vec![1]);
+    assert_eq!(field.allowed_potentials(2, 0), vec![3]);
+    assert_eq!(field.allowed_potentials(3, 0), vec![1]);
+
+    let indices = field.render().unwrap();
+    assert_eq!(field.to_entries(&indices), vec![vec![1, 2, 2, 1]]);
+}
+
+#[test]
+fn weight_is_spread_over_parts() {
+    let pieces = [
+        Piece::new("000|000|000", 1, 1).with_weight(3.0),
+        Piece::new("000|000|000", 2, 2).with_weight(8.0),
+    ];
+
+    let field = Field::new(&pieces, 4, 4);
+
+    assert_eq!(field.weight(0), 3.0);
+    for potential_index in 1..5 {
+        assert_eq!(field.weight(potential_index), 2.0);
+    }
+}
+
+#[test]
+fn pruning_propagates() {
+    // The pole needs something below it, and only the wide piece connects
+    // up.  The mask leaves no room for the wide piece in the bottom row, so
+    // the pole can not go above it either.
+    let pieces = [
+        Piece::new("000|000|010", 1, 1),
+        Piece::new("010|000|000", 2, 1),
+        Piece::new("000|000|000", 1, 1),
+    ];
+
+    let field = Field::new(&pieces, 2, 2)
+        .with_mask(&[vec![true, true], vec![true, false]])
+        .unwrap();
+
+    assert_eq!(field.allowed_potentials(0, 1), vec![0, 3]);
+    assert!(!field.is_allowed(0, 0, 0));
+}
+
+#[test]
+fn least_constraining_counts_inner_edges() {
+    // The left half of the wide piece rules out everything but its right
+    // half next to it, so the lighter single cell pieces constrain less.
+    let pieces = [
+        Piece::new("000|000|000", 1, 1),
+        Piece::new("000|000|000", 2, 1).with_weight(10.0),
+        Piece::new("000|000|000", 1, 1),
+    ];
+
+    let mut field = Field::new(&pieces, 2, 1)
+        .with_selector(Scanline)
+        .with_chooser(LeastConstraining);
+
+    let indices = field.solve(&mut Isaac64Rng::from_seed(&[0])).unwrap();
+    assert_eq!(field.to_entries(&indices), vec![vec![0, 0]]);
+}
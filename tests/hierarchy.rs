@@ -0,0 +1,102 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use wfc::chooser::ChooserFn;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::hierarchy::Hierarchy;
+
+const LAND: usize = 0;
+const SEA: usize = 1;
+
+#[test]
+fn blocks_follow_regions() {
+    let regions = [
+        CharacterEntry::build('L', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('S', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let tiles = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build(':', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let hierarchy = Hierarchy::new(Field::new(&regions, 4, 3), Field::new(&tiles, 16, 9))
+        .unwrap()
+        .with_region(LAND, &[0, 1])
+        .with_region(SEA, &[1, 2]);
+
+    assert_eq!(hierarchy.block_width(), 4);
+    assert_eq!(hierarchy.block_height(), 3);
+
+    let mut rng = Isaac64Rng::from_seed(&[3]);
+    let generated = hierarchy.generate(&mut rng).unwrap();
+
+    for (y, row) in generated.fine.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            match generated.coarse[y / 3][x / 4] {
+                LAND => assert!(*tile != 2),
+                _ => assert!(*tile != 0),
+            }
+        }
+    }
+}
+
+#[test]
+fn propagation_crosses_blocks() {
+    let regions = [
+        CharacterEntry::build('A', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('B', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let tiles = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let hierarchy = Hierarchy::new(Field::new(&regions, 2, 1), Field::new(&tiles, 4, 1))
+        .unwrap()
+        .with_region(0, &[0]);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    assert_eq!(
+        hierarchy.refine(&[vec![0, 1]], &mut rng),
+        Ok(vec![vec![0, 0, 0, 0]])
+    );
+    assert!(hierarchy.refine(&[vec![0, 1, 1]], &mut rng).is_err());
+}
+
+#[test]
+fn regions_are_merged() {
+    let regions = [CharacterEntry::build('A', 1.0, "000|000|000").unwrap()];
+
+    let tiles = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build(':', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let last_allowed = ChooserFn::new(|_, _, allowed: &[usize]| allowed.last().cloned());
+
+    let hierarchy = Hierarchy::new(
+        Field::new(&regions, 1, 1),
+        Field::new(&tiles, 1, 1).with_chooser(last_allowed),
+    ).unwrap()
+        .with_region(0, &[0])
+        .with_region(0, &[1]);
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    assert_eq!(hierarchy.refine(&[vec![0]], &mut rng), Ok(vec![vec![1]]));
+}
+
+#[test]
+fn fine_field_must_fit_blocks() {
+    let tiles = [CharacterEntry::build('.', 1.0, "000|000|000").unwrap()];
+
+    assert!(Hierarchy::new(Field::new(&tiles, 3, 2), Field::new(&tiles, 7, 4)).is_err());
+}
@@ -0,0 +1,249 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use wfc::chooser::ChooserFn;
+use wfc::connectivity::Connectivity;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::layer::{LayerRule, LayeredField};
+use wfc::selector::Scanline;
+
+const GRASS: usize = 0;
+const WATER: usize = 1;
+
+const NOTHING: usize = 0;
+const TREE: usize = 1;
+const CHEST: usize = 2;
+
+fn build_layers() -> LayeredField {
+    let terrain = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let objects = [
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('T', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('$', 1.0, "000|000|000").unwrap(),
+    ];
+
+    LayeredField::new(vec![
+        Field::new(&terrain, 5, 4),
+        Field::new(&objects, 5, 4),
+    ]).unwrap()
+        .with_rule(LayerRule::new(0, 1).allow(WATER, &[NOTHING]))
+        .unwrap()
+}
+
+#[test]
+fn layers_agree() {
+    for seed in 0..10 {
+        let mut field = build_layers();
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
+
+        while field.step(&mut rng) {}
+
+        let layers = field.render().unwrap();
+
+        for (terrain_row, object_row) in layers[0].iter().zip(&layers[1]) {
+            for (terrain, object) in terrain_row.iter().zip(object_row) {
+                if *terrain == WATER {
+                    assert_eq!(*object, NOTHING);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn restrict_propagates_across_layers() {
+    let mut field = build_layers();
+
+    assert!(field.force_potential(0, 1, 1, WATER));
+    assert_eq!(field.layer(1).allowed_potentials(1, 1), vec![NOTHING]);
+
+    assert!(field.force_potential(1, 2, 2, CHEST));
+    assert_eq!(field.layer(0).allowed_potentials(2, 2), vec![GRASS]);
+
+    assert!(!field.force_potential(1, 1, 1, TREE));
+}
+
+#[test]
+fn rule_is_checked() {
+    let terrain = [CharacterEntry::build('.', 1.0, "000|000|000").unwrap()];
+
+    let layers = || {
+        LayeredField::new(vec![
+            Field::new(&terrain, 2, 2),
+            Field::new(&terrain, 2, 2),
+        ]).unwrap()
+    };
+
+    assert!(layers().with_rule(LayerRule::new(0, 2)).is_err());
+    assert!(layers().with_rule(LayerRule::new(0, 1).allow(0, &[1])).is_err());
+    assert!(layers().with_rule(LayerRule::new(0, 1).allow(0, &[])).is_err());
+    assert!(LayeredField::new(vec![
+        Field::new(&terrain, 2, 2),
+        Field::new(&terrain, 3, 2),
+    ]).is_err());
+}
+
+// Water first, so the first choice always needs to be undone.  Objects must
+// connect (0, 0) to the far end of the first row, so water in that row leaves
+// the objects unsolvable.
+fn build_road(width: usize, height: usize) -> LayeredField {
+    let terrain = [
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let objects = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let first_allowed = || ChooserFn::new(|_, _, allowed: &[usize]| allowed.first().cloned());
+
+    LayeredField::new(vec![
+        Field::new(&terrain, width, height)
+            .with_selector(Scanline)
+            .with_chooser(first_allowed()),
+        Field::new(&objects, width, height)
+            .with_selector(Scanline)
+            .with_chooser(first_allowed())
+            .with_connectivity(Connectivity::Connected((0, 0), (width - 1, 0))),
+    ]).unwrap()
+        .with_rule(LayerRule::new(0, 1).allow(0, &[1]))
+        .unwrap()
+}
+
+#[test]
+fn contradiction_across_layers_fails_without_backtracking() {
+    let mut field = build_road(3, 1);
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    assert!(!field.step(&mut rng));
+}
+
+#[test]
+fn contradiction_across_layers_backtracks() {
+    let mut field = build_road(3, 1).allow_backtracking();
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    // Water at (0, 0) is banned, and the road it ruled out is back.
+    assert!(field.step(&mut rng));
+    assert_eq!(field.layer(0).allowed_potentials(0, 0), vec![1]);
+    assert_eq!(field.layer(1).allowed_potentials(0, 0), vec![0, 1]);
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let layers = field.render().unwrap();
+    assert_eq!(layers[0], vec![vec![1, 1, 1]]);
+    assert_eq!(layers[1], vec![vec![0, 0, 0]]);
+}
+
+fn different(layer_a: usize, layer_b: usize) -> LayerRule {
+    LayerRule::new(layer_a, layer_b)
+        .allow(0, &[1, 2])
+        .allow(1, &[0, 2])
+        .allow(2, &[0, 1])
+}
+
+#[test]
+fn backtracking_undoes_earlier_steps() {
+    // Four single cell layers colored with three colors.  Color 0 in the
+    // first layer leaves only two colors for the other three layers, which
+    // all have to differ.  Each pair of them on its own still has a solution,
+    // so that only shows up once the second layer is chosen, and the step
+    // that chose the first layer has to be undone.
+    let colors = [
+        CharacterEntry::build('r', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('g', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let layer = || {
+        Field::new(&colors, 1, 1)
+            .with_chooser(ChooserFn::new(|_, _, allowed: &[usize]| allowed.first().cloned()))
+    };
+
+    let mut field = LayeredField::new(vec![layer(), layer(), layer(), layer()])
+        .unwrap()
+        .with_rule(different(0, 1))
+        .unwrap()
+        .with_rule(LayerRule::new(0, 2).allow(0, &[1, 2]))
+        .unwrap()
+        .with_rule(LayerRule::new(0, 3).allow(0, &[1, 2]))
+        .unwrap()
+        .with_rule(different(1, 2))
+        .unwrap()
+        .with_rule(different(1, 3))
+        .unwrap()
+        .with_rule(different(2, 3))
+        .unwrap()
+        .allow_backtracking();
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    assert!(field.step(&mut rng));
+    assert_eq!(field.layer(0).allowed_potentials(0, 0), vec![0]);
+    assert_eq!(field.layer(2).allowed_potentials(0, 0), vec![1, 2]);
+
+    // Both colors of the second layer fail, so the first layer's choice is
+    // banned, along with what it removed from the third layer.
+    assert!(field.step(&mut rng));
+    assert_eq!(field.layer(0).allowed_potentials(0, 0), vec![1, 2]);
+    assert_eq!(field.layer(2).allowed_potentials(0, 0), vec![0, 1, 2]);
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let layers = field.render().unwrap();
+    let colors: Vec<usize> = layers.iter().map(|layer| layer[0][0]).collect();
+    assert_eq!(colors, vec![1, 0, 1, 2]);
+}
+
+#[test]
+fn backtracking_solves_a_full_size_map() {
+    let terrain: Vec<CharacterEntry> = "abcdefgh"
+        .chars()
+        .map(|c| CharacterEntry::build(c, 1.0, "000|000|000").unwrap())
+        .collect();
+
+    let objects = [
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('T', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('$', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = LayeredField::new(vec![
+        Field::new(&terrain, 80, 40),
+        Field::new(&objects, 80, 40),
+    ]).unwrap()
+        .with_rule(LayerRule::new(0, 1).allow(0, &[NOTHING]).allow(1, &[NOTHING, TREE]))
+        .unwrap()
+        .allow_backtracking();
+
+    let mut rng = Isaac64Rng::from_seed(&[1]);
+
+    while field.render().is_none() {
+        assert!(field.step(&mut rng));
+    }
+
+    let layers = field.render().unwrap();
+
+    for (terrain_row, object_row) in layers[0].iter().zip(&layers[1]) {
+        for (terrain, object) in terrain_row.iter().zip(object_row) {
+            match *terrain {
+                0 => assert_eq!(*object, NOTHING),
+                1 => assert_ne!(*object, CHEST),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::Isaac64Rng;
+
+use wfc::count::CountConstraint;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::observer::FieldObserver;
+
+#[derive(Default)]
+struct CountingObserver {
+    collapses: Vec<(usize, usize, usize)>,
+    invalidations: usize,
+    backtracks: Vec<(usize, usize, usize)>,
+    contradictions: usize,
+}
+
+impl FieldObserver for CountingObserver {
+    fn on_collapse(&mut self, x: usize, y: usize, potential_index: usize) {
+        self.collapses.push((x, y, potential_index));
+    }
+
+    fn on_invalidate(&mut self, _x: usize, _y: usize, _potential_index: usize) {
+        self.invalidations += 1;
+    }
+
+    fn on_backtrack(&mut self, x: usize, y: usize, potential_index: usize) {
+        self.backtracks.push((x, y, potential_index));
+    }
+
+    fn on_contradiction(&mut self, _cell: Option<(usize, usize)>) {
+        self.contradictions += 1;
+    }
+}
+
+#[test]
+fn observe_collapse_and_invalidate() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build('|', 1.0, "010|000|010").unwrap(),
+    ];
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    let mut observer = CountingObserver::default();
+
+    let mut field = Field::new(&potentials, 3, 3);
+    assert!(field.step_observed(&mut rng, &mut observer));
+
+    // Choosing any cell decides the whole field.
+    assert_eq!(observer.collapses.len(), 1);
+    assert_eq!(observer.invalidations, 8);
+    assert!(observer.backtracks.is_empty());
+    assert_eq!(observer.contradictions, 0);
+    assert!(field.render().is_some());
+}
+
+#[test]
+fn observe_backtrack_and_contradiction() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('$', 1.0, "000|000|000").unwrap(),
+    ];
+
+    // Every cell must be '.', but only one of them may be.
+    let mut field = Field::new(&potentials, 2, 1)
+        .with_count_constraint(CountConstraint::at_most(&[1], 0))
+        .with_count_constraint(CountConstraint::exactly(&[0], 1))
+        .allow_backtracking();
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    let mut observer = CountingObserver::default();
+
+    assert!(!field.step_observed(&mut rng, &mut observer));
+
+    assert_eq!(observer.collapses.len(), 1);
+    assert_eq!(observer.backtracks, observer.collapses);
+    assert!(observer.contradictions >= 2);
+}
+
+struct StopAfter(usize);
+
+impl FieldObserver for StopAfter {
+    fn on_collapse(&mut self, _x: usize, _y: usize, _potential_index: usize) {
+        self.0 = self.0.saturating_sub(1);
+    }
+
+    fn should_stop(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[test]
+fn observer_stops_solve() {
+    let potentials = [
+        CharacterEntry::build('a', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut rng = Isaac64Rng::new_unseeded();
+
+    let mut field = Field::new(&potentials, 3, 3);
+    assert!(field.solve_observed(&mut rng, &mut StopAfter(2)).is_none());
+
+    let collapsed = (0..9)
+        .filter(|i| field.num_allowed(i % 3, i / 3) == 1)
+        .count();
+    assert_eq!(collapsed, 2);
+
+    assert!(field.solve(&mut rng).is_some());
+}
@@ -0,0 +1,63 @@
+extern crate wfc;
+
+use wfc::boundary::Boundary;
+use wfc::entry::{CharacterEntry, Entry};
+use wfc::field::{Field, MASKED};
+use wfc::render::{Renderer, TextEntry, TextRenderer};
+
+struct Room {
+    name: &'static str,
+    boundary: Boundary,
+}
+
+impl Entry for Room {
+    fn weight(&self) -> f32 {
+        1.0
+    }
+
+    fn boundary(&self) -> &Boundary {
+        &self.boundary
+    }
+}
+
+impl TextEntry for Room {
+    fn text(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+#[test]
+fn render_custom_entry() {
+    let rooms = [
+        Room {
+            name: "[]",
+            boundary: Boundary::from_str("000|000|000").unwrap(),
+        },
+        Room {
+            name: "~",
+            boundary: Boundary::from_str("000|000|000").unwrap(),
+        },
+    ];
+
+    let mut field = Field::new(&rooms, 3, 1);
+    assert!(field.force_potential(0, 0, 0));
+    assert!(field.force_potential(2, 0, 1));
+
+    let renderer = TextRenderer::default().with_unknown("..");
+
+    assert_eq!(renderer.render(&rooms, &field.render_partial()), "[]..~ \n");
+}
+
+#[test]
+fn render_custom_glyphs() {
+    let potentials = [CharacterEntry::build('#', 1.0, "000|000|000").unwrap()];
+
+    let renderer = TextRenderer::default()
+        .with_unknown("_")
+        .with_contradiction("X")
+        .with_masked(".");
+
+    let indices = vec![vec![0, 1], vec![usize::MAX, MASKED]];
+
+    assert_eq!(renderer.render(&potentials, &indices), "#_\nX.\n");
+}
@@ -0,0 +1,105 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::Isaac64Rng;
+
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::observer::FieldObserver;
+use wfc::selector::{CellSelector, GrowFromFrontier, MinEntropy, MinRemainingValues, RandomCell,
+                    Scanline};
+
+#[derive(Default)]
+struct CollapseOrder {
+    cells: Vec<(usize, usize)>,
+}
+
+impl FieldObserver for CollapseOrder {
+    fn on_collapse(&mut self, x: usize, y: usize, _potential_index: usize) {
+        self.cells.push((x, y));
+    }
+}
+
+fn independent_potentials() -> Vec<CharacterEntry> {
+    vec![
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 2.0, "000|000|000").unwrap(),
+    ]
+}
+
+fn solve_with<S: CellSelector + 'static>(selector: S) -> Vec<(usize, usize)> {
+    let potentials = independent_potentials();
+
+    let mut field = Field::new(&potentials, 4, 3).with_selector(selector);
+    let mut rng = Isaac64Rng::new_unseeded();
+    let mut order = CollapseOrder::default();
+
+    while field.render().is_none() {
+        assert!(field.step_observed(&mut rng, &mut order));
+    }
+
+    order.cells
+}
+
+#[test]
+fn scanline_order() {
+    let expected: Vec<(usize, usize)> = (0..3)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .collect();
+
+    assert_eq!(solve_with(Scanline), expected);
+}
+
+#[test]
+fn grow_from_frontier_stays_connected() {
+    let order = solve_with(GrowFromFrontier);
+    assert_eq!(order.len(), 12);
+
+    for (i, &(x, y)) in order.iter().enumerate().skip(1) {
+        let touches_earlier = order[..i].iter().any(|&(earlier_x, earlier_y)| {
+            (earlier_x as isize - x as isize).abs() <= 1
+                && (earlier_y as isize - y as isize).abs() <= 1
+        });
+
+        assert!(touches_earlier, "({}, {}) does not touch the frontier", x, y);
+    }
+}
+
+#[test]
+fn every_selector_solves() {
+    assert_eq!(solve_with(MinEntropy).len(), 12);
+    assert_eq!(solve_with(MinRemainingValues).len(), 12);
+    assert_eq!(solve_with(RandomCell).len(), 12);
+}
+
+#[test]
+fn min_remaining_values_prefers_restricted_cells() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('~', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 3, 3).with_selector(MinRemainingValues);
+    assert!(field.restrict(2, 1, &[0, 1]));
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    let mut order = CollapseOrder::default();
+
+    assert!(field.step_observed(&mut rng, &mut order));
+    assert_eq!(order.cells, vec![(2, 1)]);
+}
+
+#[test]
+fn entropy_of_inactive_cells() {
+    let mask = vec![vec![true, false]];
+
+    let field = Field::new(&independent_potentials(), 2, 1)
+        .with_mask(&mask)
+        .unwrap();
+
+    assert!(field.entropy(0, 0) > 0.0);
+    assert_eq!(field.entropy(1, 0), 0.0);
+    assert_eq!(field.entropy(5, 0), 0.0);
+    assert_eq!(field.entropy(0, 5), 0.0);
+}
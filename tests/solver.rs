@@ -0,0 +1,70 @@
+extern crate wfc;
+
+use wfc::count::CountConstraint;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::solver::{solve_attempt, ParallelSolver};
+
+#[test]
+fn parallel_solution_is_reproducible() {
+    let potentials = [
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    let mut field = Field::new(&potentials, 12, 6);
+    assert!(field.close_edges());
+
+    let solution = ParallelSolver::new(5)
+        .with_threads(4)
+        .with_max_attempts(200)
+        .solve(&field)
+        .unwrap();
+
+    assert_eq!(
+        solve_attempt(&field, 5, solution.attempt),
+        Some(solution.indices)
+    );
+}
+
+#[test]
+fn parallel_solver_reports_failure() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('$', 1.0, "000|000|000").unwrap(),
+    ];
+
+    // Every cell must be '.', but only one of them may be.
+    let field = Field::new(&potentials, 2, 1)
+        .with_count_constraint(CountConstraint::at_most(&[1], 0))
+        .with_count_constraint(CountConstraint::exactly(&[0], 1));
+
+    assert!(ParallelSolver::new(1).with_threads(2).solve(&field).is_err());
+}
+
+#[test]
+fn solution_reports_deviation() {
+    let potentials = [
+        CharacterEntry::build('.', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('#', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let field = Field::new(&potentials, 10, 10).with_target_frequency(1, 0.25);
+
+    let solution = ParallelSolver::new(5).with_threads(2).solve(&field).unwrap();
+
+    assert_eq!(solution.deviation.len(), 1);
+    assert_eq!(solution.deviation[0].0, 1);
+
+    let walls = solution
+        .indices
+        .iter()
+        .flat_map(|row| row.iter())
+        .filter(|i| **i == 1)
+        .count();
+    assert_eq!(solution.deviation[0].1, walls as f32 / 100.0 - 0.25);
+}
@@ -0,0 +1,60 @@
+extern crate wfc;
+
+use wfc::count::CountConstraint;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::stats::Statistics;
+
+#[test]
+fn unconstrained_matches_weights() {
+    let potentials = [
+        CharacterEntry::build('a', 3.0, "000|000|000").unwrap(),
+        CharacterEntry::build('b', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let field = Field::new(&potentials, 10, 10);
+    let report = Statistics::new(7).with_runs(10).collect(&field);
+
+    assert_eq!(report.runs, 10);
+    assert_eq!(report.successes, 10);
+    assert_eq!(report.success_rate(), 1.0);
+    assert_eq!(report.mean_backtracks, 0.0);
+
+    assert_eq!(report.potentials[0].expected_frequency, 0.75);
+    assert_eq!(report.potentials[0].count + report.potentials[1].count, 1000);
+    assert!((report.potentials[0].observed_frequency - 0.75).abs() < 0.05);
+}
+
+#[test]
+fn propagation_skews_frequency() {
+    let potentials = [
+        CharacterEntry::build('-', 1.0, "000|101|000").unwrap(),
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+    ];
+
+    let field = Field::new(&potentials, 4, 1)
+        .allow_backtracking()
+        .with_count_constraint(CountConstraint::exactly(&[0], 0));
+    let report = Statistics::new(7).with_runs(5).collect(&field);
+
+    assert_eq!(report.successes, 5);
+    assert!(report.mean_backtracks > 0.0);
+    assert_eq!(report.potentials[0].expected_frequency, 0.5);
+    assert_eq!(report.potentials[0].observed_frequency, 0.0);
+    assert_eq!(report.potentials[1].observed_frequency, 1.0);
+}
+
+#[test]
+fn failures_are_counted() {
+    let potentials = [CharacterEntry::build('-', 1.0, "000|101|000").unwrap()];
+
+    let mut field = Field::new(&potentials, 2, 1).allow_backtracking();
+    assert!(!field.close_edges());
+
+    let report = Statistics::new(7).with_runs(3).collect(&field);
+
+    assert_eq!(report.successes, 0);
+    assert_eq!(report.success_rate(), 0.0);
+    assert_eq!(report.potentials[0].observed_frequency, 0.0);
+    assert!(report.to_string().starts_with("0 of 3 runs succeeded"));
+}
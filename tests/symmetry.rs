@@ -0,0 +1,127 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::{Isaac64Rng, SeedableRng};
+
+use wfc::entry;
+use wfc::entry::CharacterEntry;
+use wfc::field::Field;
+use wfc::symmetry::Symmetry;
+
+fn diagonals() -> Vec<CharacterEntry> {
+    vec![
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('╱', 1.0, "001|000|100").unwrap(),
+        CharacterEntry::build('╲', 1.0, "100|000|001").unwrap(),
+        CharacterEntry::build('╳', 1.0, "101|000|101").unwrap(),
+    ]
+}
+
+#[test]
+fn force_mirrors_potential() {
+    let potentials = diagonals();
+
+    let mut field = Field::new(&potentials, 4, 3).with_symmetry(Symmetry::Horizontal)
+        .unwrap();
+    assert!(field.force_potential(0, 0, 1));
+    assert_eq!(field.allowed_potentials(3, 0), vec![2]);
+
+    let mut field = Field::new(&potentials, 4, 3).with_symmetry(Symmetry::Rotational)
+        .unwrap();
+    assert!(field.force_potential(0, 0, 1));
+    assert_eq!(field.allowed_potentials(3, 2), vec![1]);
+}
+
+#[test]
+fn center_holds_symmetric_potentials() {
+    let potentials = diagonals();
+
+    let field = Field::new(&potentials, 3, 2).with_symmetry(Symmetry::Horizontal)
+        .unwrap();
+
+    assert_eq!(field.allowed_potentials(1, 0), vec![0, 3]);
+    assert_eq!(field.allowed_potentials(0, 0), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn solutions_are_symmetric() {
+    let potentials = [
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    for symmetry in &[Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Rotational] {
+        for seed in 0..5 {
+            let mut field = Field::new(&potentials, 7, 6)
+                .allow_backtracking()
+                .with_symmetry(*symmetry)
+                .unwrap();
+            assert!(field.close_edges());
+
+            let indices = field.solve(&mut Isaac64Rng::from_seed(&[seed])).unwrap();
+            let text = entry::make_string(&potentials, &indices);
+            let lines: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+
+            for y in 0..6 {
+                for x in 0..7 {
+                    let (image_x, image_y) = symmetry.cell(x, y, 7, 6);
+                    let image = lines[image_y][image_x];
+
+                    let expected = match (*symmetry, lines[y][x]) {
+                        (_, c) if c == ' ' || c == '─' || c == '│' => c,
+                        (Symmetry::Horizontal, '┌') => '┐',
+                        (Symmetry::Horizontal, '└') => '┘',
+                        (Symmetry::Horizontal, '┐') => '┌',
+                        (Symmetry::Horizontal, '┘') => '└',
+                        (Symmetry::Vertical, '┌') => '└',
+                        (Symmetry::Vertical, '┐') => '┘',
+                        (Symmetry::Vertical, '└') => '┌',
+                        (Symmetry::Vertical, '┘') => '┐',
+                        (_, '┌') => '┘',
+                        (_, '┘') => '┌',
+                        (_, '┐') => '└',
+                        (_, '└') => '┐',
+                        (_, c) => panic!("Unexpected '{}'", c),
+                    };
+
+                    assert_eq!(image, expected, "{:?}\n{}", symmetry, text);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn center_without_symmetric_potentials() {
+    let potentials = [
+        CharacterEntry::build('╱', 1.0, "001|000|100").unwrap(),
+        CharacterEntry::build('╲', 1.0, "100|000|001").unwrap(),
+    ];
+
+    // The middle column is its own image, and neither diagonal is.
+    assert!(Field::new(&potentials, 3, 2).with_symmetry(Symmetry::Horizontal).is_err());
+    assert!(Field::new(&potentials, 4, 2).with_symmetry(Symmetry::Horizontal).is_ok());
+}
+
+#[test]
+fn symmetry_propagates() {
+    let potentials = [
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('╷', 1.0, "000|000|010").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ];
+
+    // The corner has no mirror image, and it was the only thing the stub
+    // could connect to below it.
+    let field = Field::new(&potentials, 1, 2)
+        .with_symmetry(Symmetry::Horizontal)
+        .unwrap();
+
+    assert_eq!(field.allowed_potentials(0, 0), vec![0]);
+    assert_eq!(field.allowed_potentials(0, 1), vec![0, 1]);
+}
@@ -0,0 +1,159 @@
+extern crate rand;
+extern crate wfc;
+
+use rand::Isaac64Rng;
+
+use wfc::boundary::{Boundary, Direction};
+use wfc::entry;
+use wfc::entry::{CharacterEntry, Entry};
+use wfc::field::{Field, MASKED};
+use wfc::verify::{Verifier, Violation};
+
+fn box_potentials() -> Vec<CharacterEntry> {
+    vec![
+        CharacterEntry::build(' ', 1.0, "000|000|000").unwrap(),
+        CharacterEntry::build('─', 10.0, "000|101|000").unwrap(),
+        CharacterEntry::build('│', 10.0, "010|000|010").unwrap(),
+        CharacterEntry::build('┌', 1.0, "000|001|010").unwrap(),
+        CharacterEntry::build('┐', 1.0, "000|100|010").unwrap(),
+        CharacterEntry::build('└', 1.0, "010|001|000").unwrap(),
+        CharacterEntry::build('┘', 1.0, "010|100|000").unwrap(),
+    ]
+}
+
+fn grid(potentials: &[CharacterEntry], text: &str) -> Vec<Vec<usize>> {
+    entry::parse_string(potentials, text)
+        .unwrap()
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.unwrap()).collect())
+        .collect()
+}
+
+#[test]
+fn solved_field_is_valid() {
+    let potentials = box_potentials();
+
+    let mut field = Field::new(&potentials, 10, 6).allow_backtracking();
+    assert!(field.close_edges());
+
+    let mut rng = Isaac64Rng::new_unseeded();
+    while field.step(&mut rng) {}
+
+    let verifier = Verifier::new(&potentials).with_closed_edges();
+    assert_eq!(verifier.verify(&field.render().unwrap()), vec![]);
+}
+
+#[test]
+fn edited_cell_is_reported() {
+    let potentials = box_potentials();
+    let verifier = Verifier::new(&potentials).with_closed_edges();
+
+    assert_eq!(verifier.verify(&grid(&potentials, "┌┐\n└┘")), vec![]);
+
+    assert_eq!(
+        verifier.verify(&grid(&potentials, "┌─\n└┘")),
+        vec![
+            Violation::Edge {
+                x: 1,
+                y: 0,
+                direction: Direction::East,
+                potential_index: 1,
+            },
+            Violation::Neighbor {
+                x: 1,
+                y: 0,
+                direction: Direction::South,
+                potential_index: 1,
+                neighbor_potential: 6,
+            },
+        ]
+    );
+}
+
+#[test]
+fn open_edges_are_allowed() {
+    let potentials = box_potentials();
+    let indices = grid(&potentials, "──\n  ");
+
+    assert_eq!(Verifier::new(&potentials).verify(&indices), vec![]);
+    assert_eq!(
+        Verifier::new(&potentials).with_closed_edges().verify(&indices).len(),
+        2
+    );
+}
+
+#[test]
+fn invalid_and_masked_cells() {
+    let potentials = box_potentials();
+    let verifier = Verifier::new(&potentials).with_closed_edges();
+
+    assert_eq!(
+        verifier.verify(&[vec![MASKED, 0, 7]]),
+        vec![Violation::Invalid {
+            x: 2,
+            y: 0,
+            potential_index: 7,
+        }]
+    );
+}
+
+#[test]
+fn masked_neighbors_are_outside() {
+    let potentials = box_potentials();
+    let indices = vec![vec![1, MASKED], vec![0, 0]];
+
+    assert_eq!(Verifier::new(&potentials).verify(&indices), vec![]);
+    assert_eq!(
+        Verifier::new(&potentials).with_closed_edges().verify(&indices),
+        vec![
+            Violation::Edge {
+                x: 0,
+                y: 0,
+                direction: Direction::East,
+                potential_index: 1,
+            },
+            Violation::Edge {
+                x: 0,
+                y: 0,
+                direction: Direction::West,
+                potential_index: 1,
+            },
+        ]
+    );
+}
+
+struct Wide {
+    boundary: Boundary,
+}
+
+impl Entry for Wide {
+    fn weight(&self) -> f32 {
+        1.0
+    }
+
+    fn boundary(&self) -> &Boundary {
+        &self.boundary
+    }
+
+    fn footprint(&self) -> (usize, usize) {
+        (2, 1)
+    }
+}
+
+#[test]
+fn footprint_into_mask() {
+    let verifier = Verifier::new(&[Wide {
+        boundary: Boundary::default(),
+    }]);
+
+    assert_eq!(verifier.verify(&[vec![0, 1]]), vec![]);
+    assert_eq!(
+        verifier.verify(&[vec![0, MASKED]]),
+        vec![Violation::Edge {
+            x: 0,
+            y: 0,
+            direction: Direction::East,
+            potential_index: 0,
+        }]
+    );
+}